@@ -0,0 +1,262 @@
+use std::collections::HashMap;
+use std::fmt::Write;
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+
+use crate::error::{parse_num, ParseError, Result};
+use crate::solution::Solution;
+
+/// Header of the CSV that timings are written as.
+const CSV_HEADER: &str = "day,phase,min_ns,median_ns,max_ns";
+
+/// Phase is a timed step of solving a puzzle.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum Phase {
+    Parse,
+    Part1,
+    Part2,
+}
+
+impl Phase {
+    /// Every phase, in the order they run.
+    pub const ALL: [Phase; 3] = [Phase::Parse, Phase::Part1, Phase::Part2];
+
+    /// Returns the name of this phase, like `part1`.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Phase::Parse => "parse",
+            Phase::Part1 => "part1",
+            Phase::Part2 => "part2",
+        }
+    }
+
+    /// Returns the phase with the given name.
+    pub fn from_name(name: &str) -> Option<Phase> {
+        Phase::ALL.into_iter().find(|phase| phase.name() == name)
+    }
+}
+
+/// Stats summarizes the wall time of several runs of a phase.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub struct Stats {
+    pub min: Duration,
+    pub median: Duration,
+    pub max: Duration,
+}
+
+impl Stats {
+    /// Summarizes the given samples, which must not be empty.
+    pub fn new(mut samples: Vec<Duration>) -> Self {
+        samples.sort();
+
+        let mid = samples.len() / 2;
+        let median = if samples.len().is_multiple_of(2) {
+            (samples[mid - 1] + samples[mid]) / 2
+        } else {
+            samples[mid]
+        };
+
+        Stats { min: samples[0], median, max: samples[samples.len() - 1] }
+    }
+}
+
+/// Timings holds the stats for each phase of solving a puzzle.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub struct Timings {
+    pub parse: Stats,
+    pub part1: Stats,
+    pub part2: Stats,
+}
+
+impl Timings {
+    /// Returns the stats for the given phase.
+    pub fn phase(&self, phase: Phase) -> &Stats {
+        match phase {
+            Phase::Parse => &self.parse,
+            Phase::Part1 => &self.part1,
+            Phase::Part2 => &self.part2,
+        }
+    }
+}
+
+/// Parses the input and solves both parts of the puzzle the given number of times (at least
+/// once), timing each phase.  Parts are always solved from a freshly parsed puzzle.
+pub fn measure<S: Solution>(input: &str, runs: usize) -> Result<Timings> {
+    let runs = runs.max(1);
+    let (mut parse, mut part1, mut part2) = (Vec::new(), Vec::new(), Vec::new());
+
+    for _ in 0..runs {
+        let start = Instant::now();
+        let puzzle = S::parse(black_box(input))?;
+        parse.push(start.elapsed());
+
+        let start = Instant::now();
+        black_box(S::part1(&puzzle)?);
+        part1.push(start.elapsed());
+
+        let start = Instant::now();
+        black_box(S::part2(&puzzle)?);
+        part2.push(start.elapsed());
+    }
+
+    Ok(Timings { parse: Stats::new(parse), part1: Stats::new(part1), part2: Stats::new(part2) })
+}
+
+/// Baseline holds the median timings of an earlier run, read back from its CSV.
+#[derive(Debug, Default)]
+pub struct Baseline {
+    medians: HashMap<(u32, Phase), Duration>,
+}
+
+impl Baseline {
+    /// Parses a baseline from CSV written by `to_csv`.
+    pub fn parse(text: &str) -> std::result::Result<Self, ParseError> {
+        let mut lines = text.lines();
+
+        match lines.next() {
+            Some(header) if header.trim() == CSV_HEADER => {}
+            _ => return Err(ParseError::new(1, format!("expected a header of '{}'", CSV_HEADER))),
+        }
+
+        let mut medians = HashMap::new();
+        for (i, line) in lines.enumerate() {
+            if line.trim().is_empty() {
+                continue;
+            }
+
+            let on_line = |e: ParseError| e.on_line(i + 2);
+            let fields = line.split(',').collect::<Vec<&str>>();
+            if fields.len() != 5 {
+                return Err(on_line(ParseError::new(1, format!("expected 5 fields, found {}", fields.len()))));
+            }
+
+            let day = parse_num(line, fields[0]).map_err(on_line)?;
+            let phase = Phase::from_name(fields[1])
+                .ok_or_else(|| on_line(ParseError::at(line, fields[1], format!("unknown phase '{}'", fields[1]))))?;
+            let median = parse_num(line, fields[3]).map_err(on_line)?;
+
+            medians.insert((day, phase), Duration::from_nanos(median));
+        }
+
+        Ok(Baseline { medians })
+    }
+
+    /// Returns the median time of a day's phase in the baseline, if it was timed.
+    pub fn median(&self, day: u32, phase: Phase) -> Option<Duration> {
+        self.medians.get(&(day, phase)).copied()
+    }
+}
+
+/// Returns the timings as CSV, with one row per day and phase.  Times are in nanoseconds.
+pub fn to_csv(results: &[(u32, Timings)]) -> String {
+    let mut csv = format!("{}\n", CSV_HEADER);
+
+    for (day, timings) in results {
+        for phase in Phase::ALL {
+            let stats = timings.phase(phase);
+            writeln!(csv, "{},{},{},{},{}", day, phase.name(),
+                     stats.min.as_nanos(), stats.median.as_nanos(), stats.max.as_nanos()).unwrap();
+        }
+    }
+
+    csv
+}
+
+/// Returns a table of the timings for people to read.  If there's a baseline, the table includes
+/// the change in median time since the baseline.
+pub fn table(results: &[(u32, Timings)], baseline: Option<&Baseline>) -> String {
+    let mut table = format!("{:>3}  {:<5}  {:>10}  {:>10}  {:>10}", "Day", "Phase", "Min", "Median", "Max");
+    if baseline.is_some() {
+        write!(table, "  {:>8}", "Change").unwrap();
+    }
+    table.push('\n');
+
+    for (day, timings) in results {
+        for phase in Phase::ALL {
+            let stats = timings.phase(phase);
+            write!(table, "{:>3}  {:<5}  {:>10}  {:>10}  {:>10}", day, phase.name(),
+                   format_duration(stats.min), format_duration(stats.median), format_duration(stats.max)).unwrap();
+
+            if let Some(baseline) = baseline {
+                let change = baseline.median(*day, phase)
+                    .map(|before| format_change(before, stats.median))
+                    .unwrap_or_else(|| "-".to_string());
+                write!(table, "  {:>8}", change).unwrap();
+            }
+
+            table.push('\n');
+        }
+    }
+
+    table
+}
+
+/// Formats a duration with a unit that suits its size, like `12.3ms`.
+pub fn format_duration(d: Duration) -> String {
+    let nanos = d.as_nanos();
+
+    if nanos < 1_000 {
+        format!("{}ns", nanos)
+    } else if nanos < 1_000_000 {
+        format!("{:.1}µs", nanos as f64 / 1e3)
+    } else if nanos < 1_000_000_000 {
+        format!("{:.1}ms", nanos as f64 / 1e6)
+    } else {
+        format!("{:.2}s", nanos as f64 / 1e9)
+    }
+}
+
+/// Formats the change from one duration to another as a percentage, like `+5.2%`.
+fn format_change(before: Duration, after: Duration) -> String {
+    if before.is_zero() {
+        return "-".to_string();
+    }
+
+    let change = (after.as_nanos() as f64 - before.as_nanos() as f64) / before.as_nanos() as f64;
+    format!("{:+.1}%", change * 100.0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn micros(us: &[u64]) -> Vec<Duration> {
+        us.iter().map(|&us| Duration::from_micros(us)).collect()
+    }
+
+    #[test]
+    fn stats_median() {
+        assert_eq!(Stats { min: Duration::from_micros(1), median: Duration::from_micros(3), max: Duration::from_micros(9) },
+                   Stats::new(micros(&[9, 1, 3])));
+        assert_eq!(Duration::from_micros(4), Stats::new(micros(&[9, 1, 3, 5])).median);
+    }
+
+    #[test]
+    fn csv_round_trip() {
+        let stats = |us| Stats::new(micros(&[us]));
+        let results = vec![(3, Timings { parse: stats(1), part1: stats(2), part2: stats(3) })];
+
+        let csv = to_csv(&results);
+        assert_eq!("day,phase,min_ns,median_ns,max_ns\n3,parse,1000,1000,1000\n3,part1,2000,2000,2000\n3,part2,3000,3000,3000\n", csv);
+
+        let baseline = Baseline::parse(&csv).unwrap();
+        assert_eq!(Some(Duration::from_micros(2)), baseline.median(3, Phase::Part1));
+        assert_eq!(None, baseline.median(4, Phase::Part1));
+    }
+
+    #[test]
+    fn parse_invalid_baseline() {
+        assert_eq!(Some(ParseError::new(3, "unknown phase 'part3'").on_line(2)),
+                   Baseline::parse("day,phase,min_ns,median_ns,max_ns\n3,part3,1,2,3\n").err());
+    }
+
+    #[test]
+    fn formatting() {
+        assert_eq!("999ns", format_duration(Duration::from_nanos(999)));
+        assert_eq!("12.3µs", format_duration(Duration::from_nanos(12_345)));
+        assert_eq!("1.5ms", format_duration(Duration::from_micros(1_500)));
+        assert_eq!("2.00s", format_duration(Duration::from_secs(2)));
+        assert_eq!("+50.0%", format_change(Duration::from_micros(2), Duration::from_micros(3)));
+        assert_eq!("-25.0%", format_change(Duration::from_micros(4), Duration::from_micros(3)));
+    }
+}
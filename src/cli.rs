@@ -0,0 +1,236 @@
+use adventofcode_2021::runner::{self, Format, Part};
+
+pub const USAGE: &str = "\
+Usage: adventofcode-2021 <command>
+
+Commands:
+  run <day> [--part <1|2>] [--input <path>] [--json]
+      Solves a day's puzzle.  Solves both parts unless --part is given, and reads
+      input/day<day>.txt unless --input is given.  An input of - reads stdin.
+      --json prints the answers, timing, and any errors as a JSON record.
+  bench [<day>...] [--runs <n>] [--output <path>] [--baseline <path>]
+      Times parsing and solving each part of the given days, or every day, using
+      their default inputs.  Each day runs 10 times unless --runs is given.
+      --output writes the timings as CSV, and --baseline compares them against
+      CSV from an earlier run.
+  all [--json]
+      Solves every day in parallel using their default inputs, and prints a table
+      of answers and timings.  --json prints a JSON record per day instead.
+  verify [<day>...] [--answers <path>]
+      Checks the given days, or every day, against the answers recorded in
+      answers.tsv unless --answers is given.
+  odds [--csv]
+      Prints the chance that player 1 wins day 21's Dirac dice game, and the expected
+      number of turns, for every pair of starting positions.  --csv prints both
+      players' win counts and chances as CSV instead.
+  help
+      Prints this message.";
+
+/// Manifest of recorded answers that `verify` checks by default.
+const DEFAULT_ANSWERS: &str = "answers.tsv";
+
+/// Command is an action requested on the command line.
+#[derive(Debug, Eq, PartialEq)]
+pub enum Command {
+    Run { day: u32, part: Part, input: String, format: Format },
+    Bench { days: Vec<u32>, runs: usize, output: Option<String>, baseline: Option<String> },
+    All { format: Format },
+    Verify { days: Vec<u32>, answers: String },
+    Odds { csv: bool },
+    Help,
+}
+
+impl Command {
+    /// Parses a command from the given arguments, which don't include the program name.
+    pub fn parse(args: &[String]) -> Result<Command, String> {
+        let mut args = args.iter().map(|arg| arg.as_str());
+
+        match args.next() {
+            Some("run") => Self::parse_run(args),
+            Some("bench") => Self::parse_bench(args),
+            Some("all") => Self::parse_all(args),
+            Some("verify") => Self::parse_verify(args),
+            Some("odds") => Self::parse_odds(args),
+            Some("help") | Some("--help") | Some("-h") | None => Ok(Command::Help),
+            Some(other) => Err(format!("Unknown command '{}'.", other)),
+        }
+    }
+
+    /// Parses the arguments of the `run` command, like `15 --part 2 --input path`.
+    fn parse_run<'a>(mut args: impl Iterator<Item=&'a str>) -> Result<Command, String> {
+        let day = parse_day(args.next())?;
+        let mut part = Part::Both;
+        let mut input = None;
+        let mut format = Format::Text;
+
+        while let Some(arg) = args.next() {
+            match arg {
+                "--part" | "-p" => part = parse_part(args.next())?,
+                "--input" | "-i" => input = Some(flag_value(arg, args.next())?.to_string()),
+                "--json" => format = Format::Json,
+                _ => return Err(format!("Unexpected argument '{}'.", arg)),
+            }
+        }
+
+        let input = input.unwrap_or_else(|| runner::default_input(day));
+
+        Ok(Command::Run { day, part, input, format })
+    }
+
+    /// Parses the arguments of the `all` command, like `--json`.
+    fn parse_all<'a>(args: impl Iterator<Item=&'a str>) -> Result<Command, String> {
+        let mut format = Format::Text;
+
+        for arg in args {
+            match arg {
+                "--json" => format = Format::Json,
+                _ => return Err(format!("Unexpected argument '{}'.", arg)),
+            }
+        }
+
+        Ok(Command::All { format })
+    }
+
+    /// Parses the arguments of the `bench` command, like `15 17 --runs 5 --output timings.csv`.
+    fn parse_bench<'a>(mut args: impl Iterator<Item=&'a str>) -> Result<Command, String> {
+        let mut days = Vec::new();
+        let mut runs = 10;
+        let mut output = None;
+        let mut baseline = None;
+
+        while let Some(arg) = args.next() {
+            match arg {
+                "--runs" | "-r" => runs = parse_runs(args.next())?,
+                "--output" | "-o" => output = Some(flag_value(arg, args.next())?.to_string()),
+                "--baseline" | "-b" => baseline = Some(flag_value(arg, args.next())?.to_string()),
+                _ if arg.starts_with('-') => return Err(format!("Unexpected argument '{}'.", arg)),
+                _ => days.push(parse_day(Some(arg))?),
+            }
+        }
+
+        if days.is_empty() {
+            days = runner::DAYS.to_vec();
+        }
+
+        Ok(Command::Bench { days, runs, output, baseline })
+    }
+
+    /// Parses the arguments of the `verify` command, like `14 18 --answers answers.tsv`.
+    fn parse_verify<'a>(mut args: impl Iterator<Item=&'a str>) -> Result<Command, String> {
+        let mut days = Vec::new();
+        let mut answers = DEFAULT_ANSWERS.to_string();
+
+        while let Some(arg) = args.next() {
+            match arg {
+                "--answers" | "-a" => answers = flag_value(arg, args.next())?.to_string(),
+                _ if arg.starts_with('-') => return Err(format!("Unexpected argument '{}'.", arg)),
+                _ => days.push(parse_day(Some(arg))?),
+            }
+        }
+
+        Ok(Command::Verify { days, answers })
+    }
+
+    /// Parses the arguments of the `odds` command, like `--csv`.
+    fn parse_odds<'a>(args: impl Iterator<Item=&'a str>) -> Result<Command, String> {
+        let mut csv = false;
+
+        for arg in args {
+            match arg {
+                "--csv" => csv = true,
+                _ => return Err(format!("Unexpected argument '{}'.", arg)),
+            }
+        }
+
+        Ok(Command::Odds { csv })
+    }
+}
+
+/// Parses a day number.
+fn parse_day(arg: Option<&str>) -> Result<u32, String> {
+    let arg = arg.ok_or_else(|| "Missing day.".to_string())?;
+
+    arg.parse().map_err(|_| format!("Day must be a number, but was '{}'.", arg))
+}
+
+/// Parses the value of a `--part` flag, which must be 1 or 2.
+fn parse_part(arg: Option<&str>) -> Result<Part, String> {
+    match flag_value("--part", arg)? {
+        "1" => Ok(Part::One),
+        "2" => Ok(Part::Two),
+        other => Err(format!("Part must be 1 or 2, but was '{}'.", other)),
+    }
+}
+
+/// Parses the value of a `--runs` flag, which must be a positive number.
+fn parse_runs(arg: Option<&str>) -> Result<usize, String> {
+    match flag_value("--runs", arg)?.parse() {
+        Ok(runs) if runs > 0 => Ok(runs),
+        _ => Err(format!("Runs must be a positive number, but was '{}'.", arg.unwrap())),
+    }
+}
+
+/// Returns the value that follows a flag, or an error if the value is missing.
+fn flag_value<'a>(flag: &str, arg: Option<&'a str>) -> Result<&'a str, String> {
+    arg.ok_or_else(|| format!("Missing value for {}.", flag))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(args: &str) -> Result<Command, String> {
+        let args = args.split_whitespace().map(|s| s.to_string()).collect::<Vec<String>>();
+        Command::parse(&args)
+    }
+
+    #[test]
+    fn parse_run() {
+        assert_eq!(Ok(Command::Run { day: 15, part: Part::Both, input: "input/day15.txt".to_string(), format: Format::Text }), parse("run 15"));
+        assert_eq!(Ok(Command::Run { day: 15, part: Part::Two, input: "input/day15.txt".to_string(), format: Format::Text }), parse("run 15 --part 2"));
+        assert_eq!(Ok(Command::Run { day: 3, part: Part::One, input: "other.txt".to_string(), format: Format::Json }), parse("run 3 -i other.txt --json -p 1"));
+    }
+
+    #[test]
+    fn parse_invalid() {
+        assert_eq!(Err("Missing day.".to_string()), parse("run"));
+        assert_eq!(Err("Day must be a number, but was 'x'.".to_string()), parse("run x"));
+        assert_eq!(Err("Part must be 1 or 2, but was '3'.".to_string()), parse("run 1 --part 3"));
+        assert_eq!(Err("Missing value for --input.".to_string()), parse("run 1 --input"));
+        assert_eq!(Err("Unknown command 'walk'.".to_string()), parse("walk 1"));
+    }
+
+    #[test]
+    fn parse_bench() {
+        assert_eq!(Ok(Command::Bench { days: runner::DAYS.to_vec(), runs: 10, output: None, baseline: None }), parse("bench"));
+        assert_eq!(Ok(Command::Bench { days: vec![15, 17], runs: 3, output: Some("new.csv".to_string()), baseline: Some("old.csv".to_string()) }),
+                   parse("bench 15 --runs 3 17 -o new.csv --baseline old.csv"));
+        assert_eq!(Err("Runs must be a positive number, but was '0'.".to_string()), parse("bench 1 --runs 0"));
+    }
+
+    #[test]
+    fn parse_verify() {
+        assert_eq!(Ok(Command::Verify { days: vec![], answers: "answers.tsv".to_string() }), parse("verify"));
+        assert_eq!(Ok(Command::Verify { days: vec![14, 18], answers: "other.tsv".to_string() }), parse("verify 14 -a other.tsv 18"));
+    }
+
+    #[test]
+    fn parse_all() {
+        assert_eq!(Ok(Command::All { format: Format::Text }), parse("all"));
+        assert_eq!(Ok(Command::All { format: Format::Json }), parse("all --json"));
+        assert_eq!(Err("Unexpected argument '3'.".to_string()), parse("all 3"));
+    }
+
+    #[test]
+    fn parse_odds() {
+        assert_eq!(Ok(Command::Odds { csv: false }), parse("odds"));
+        assert_eq!(Ok(Command::Odds { csv: true }), parse("odds --csv"));
+        assert_eq!(Err("Unexpected argument '--json'.".to_string()), parse("odds --json"));
+    }
+
+    #[test]
+    fn parse_help() {
+        assert_eq!(Ok(Command::Help), parse(""));
+        assert_eq!(Ok(Command::Help), parse("help"));
+    }
+}
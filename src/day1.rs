@@ -1,47 +1,127 @@
-use std::fs::File;
-use std::io::{BufRead, BufReader};
+use std::cmp::Ordering;
+use std::collections::VecDeque;
+use std::io::BufRead;
 
-#[allow(dead_code)]
-pub fn solution() {
-    let measurements = load_measurements("input/day1.txt");
+use crate::error::{parse_num, ParseError, Result};
+use crate::solution::Solution;
 
-    println!("Part 1: {}", num_increasing(&measurements));
-    println!("Part 2: {}", num_increasing_windows(&measurements));
+/// Day 1: Sonar Sweep.
+pub struct Day1;
+
+impl Solution for Day1 {
+    type Puzzle = Vec<i32>;
+    type Part1 = usize;
+    type Part2 = usize;
+
+    fn load<R: BufRead>(reader: R) -> Result<Self::Puzzle> {
+        load_measurements(reader)
+    }
+
+    fn part1(measurements: &Self::Puzzle) -> Result<Self::Part1> {
+        Ok(num_increasing(measurements))
+    }
+
+    fn part2(measurements: &Self::Puzzle) -> Result<Self::Part2> {
+        Ok(num_increasing_windows(measurements))
+    }
+}
+
+/// Loads measurements from the given input, which contains one depth per line.
+pub fn load_measurements<R: BufRead>(reader: R) -> Result<Vec<i32>> {
+    read_depths(reader).collect()
+}
+
+/// Returns an iterator over the depths in the given input, which contains one depth per line.
+/// Depths are read as they're needed, so inputs larger than memory can be swept with
+/// `itertools::process_results(read_depths(reader), |depths| sweep(depths, 3))`.
+pub fn read_depths<R: BufRead>(reader: R) -> impl Iterator<Item = Result<i32>> {
+    reader.lines().enumerate().filter_map(|(i, line)| {
+        let line = match line {
+            Ok(line) => line,
+            Err(e) => return Some(Err(e.into())),
+        };
+        let depth = line.trim();
+
+        if depth.is_empty() {
+            None
+        } else {
+            Some(parse_num(&line, depth).map_err(|e: ParseError| e.on_line(i + 1).into()))
+        }
+    })
 }
 
-#[allow(dead_code)]
-fn load_measurements(filename: &str) -> Vec<i32> {
-    let f = File::open(filename).unwrap();
-    let f = BufReader::new(f);
+/// Sweep is how the sums of sliding windows of measurements change from one window to the next.
+#[derive(Debug, Default, Copy, Clone, Eq, PartialEq)]
+pub struct Sweep {
+    /// Number of windows with a larger sum than the window before.
+    pub increases: usize,
+    /// Number of windows with a smaller sum than the window before.
+    pub decreases: usize,
+    /// Number of windows with the same sum as the window before.
+    pub plateaus: usize,
+    /// Most increases in a row.
+    pub longest_increasing_run: usize,
+}
+
+/// Compares the sum of each window of `window` measurements with the sum of the window before.
+/// Neighboring windows share every measurement but the first of one and the last of the other,
+/// so only the last `window` measurements are kept.
+///
+/// # Panics
+///
+/// Panics if `window` is 0.
+pub fn sweep<I: IntoIterator<Item = i32>>(depths: I, window: usize) -> Sweep {
+    assert!(window > 0, "window size must be at least 1");
+
+    let mut sweep = Sweep::default();
+    let mut run = 0;
+    let mut recent = VecDeque::with_capacity(window);
+
+    for depth in depths {
+        if recent.len() < window {
+            recent.push_back(depth);
+            continue;
+        }
 
-    f.lines()
-        .flat_map(|line| line.unwrap().parse::<i32>())
-        .collect()
+        let dropped = recent.pop_front().unwrap();
+        recent.push_back(depth);
+
+        match depth.cmp(&dropped) {
+            Ordering::Greater => {
+                sweep.increases += 1;
+                run += 1;
+                sweep.longest_increasing_run = sweep.longest_increasing_run.max(run);
+            }
+            Ordering::Less => {
+                sweep.decreases += 1;
+                run = 0;
+            }
+            Ordering::Equal => {
+                sweep.plateaus += 1;
+                run = 0;
+            }
+        }
+    }
+
+    sweep
 }
 
 /// Returns the number of measurements that increased from one to another.
 /// For example, `1 3 2` would return 1 because 3 is the only increasing measurement.
-fn num_increasing(measurements: &Vec<i32>) -> usize {
-    measurements.iter()
-        .zip(measurements.iter().skip(1))
-        .filter(|(a, b)| a < b)
-        .count()
+pub fn num_increasing(measurements: &[i32]) -> usize {
+    sweep(measurements.iter().copied(), 1).increases
 }
 
 /// Returns the number of times that the sum of three-measurement windows
 /// increases over the measurements.
-fn num_increasing_windows(measurements: &Vec<i32>) -> usize {
-    let sums = measurements.windows(3)
-        .map(|w| w.iter().sum())
-        .collect::<Vec<i32>>();
-
-    sums.iter().zip(sums.iter().skip(1))
-        .filter(|(a, b)| a < b)
-        .count()
+pub fn num_increasing_windows(measurements: &[i32]) -> usize {
+    sweep(measurements.iter().copied(), 3).increases
 }
 
 #[cfg(test)]
 mod tests {
+    use crate::error::Error;
+
     use super::*;
 
     #[test]
@@ -55,4 +135,32 @@ mod tests {
         let measurements = vec![199, 200, 208, 210, 200, 207, 240, 269, 260, 263];
         assert_eq!(5, num_increasing_windows(&measurements));
     }
+
+    #[test]
+    fn test_sweep() {
+        let measurements = vec![199, 200, 208, 210, 200, 207, 240, 269, 260, 263];
+        assert_eq!(Sweep { increases: 7, decreases: 2, plateaus: 0, longest_increasing_run: 3 }, sweep(measurements.clone(), 1));
+        assert_eq!(Sweep { increases: 5, decreases: 1, plateaus: 1, longest_increasing_run: 4 }, sweep(measurements.clone(), 3));
+
+        // Windows as large as the input don't have anything to compare.
+        assert_eq!(Sweep::default(), sweep(measurements, 10));
+
+        let depths = read_depths("199\n200\n\n208\n".as_bytes());
+        assert_eq!(Sweep { increases: 1, longest_increasing_run: 1, ..Sweep::default() }, itertools::process_results(depths, |depths| sweep(depths, 2)).unwrap());
+    }
+
+    #[test]
+    fn test_solution() {
+        let measurements = Day1::parse("199\n200\n208\n210\n200\n207\n240\n269\n260\n263\n").unwrap();
+        assert_eq!(7, Day1::part1(&measurements).unwrap());
+        assert_eq!(5, Day1::part2(&measurements).unwrap());
+    }
+
+    #[test]
+    fn test_load_invalid() {
+        match Day1::parse("199\n2x0\n") {
+            Err(Error::Parse(e)) => assert_eq!(ParseError { line: 2, column: 1, message: "expected a number, found '2x0'".to_string() }, e),
+            other => panic!("Expected a parse error, got {:?}", other),
+        }
+    }
 }
\ No newline at end of file
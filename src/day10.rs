@@ -1,53 +1,79 @@
 use std::collections::HashMap;
-use std::fs::File;
-use std::io::{BufRead, BufReader};
+use std::io::BufRead;
+use std::str::FromStr;
 use itertools::Itertools;
 
-#[allow(dead_code)]
-pub fn solution() {
-    let lines = load("input/day10.txt");
+use crate::error::{parse_lines, Error, ParseError, Result};
+use crate::solution::Solution;
 
-    println!("Part 1: {}", syntax_score(&lines));
-    println!("Part 2: {}", autocomplete_score(&lines));
-}
+/// Day 10: Syntax Scoring.
+pub struct Day10;
+
+impl Solution for Day10 {
+    type Puzzle = Vec<Line>;
+    type Part1 = i32;
+    type Part2 = i64;
+
+    fn load<R: BufRead>(reader: R) -> Result<Self::Puzzle> {
+        load(reader)
+    }
+
+    fn part1(lines: &Self::Puzzle) -> Result<Self::Part1> {
+        Ok(syntax_score(lines))
+    }
 
-fn load(filename: &str) -> Vec<Line> {
-    let f = File::open(filename).unwrap();
-    let f = BufReader::new(f);
+    fn part2(lines: &Self::Puzzle) -> Result<Self::Part2> {
+        autocomplete_score(lines)
+    }
+}
 
-    f.lines()
-        .map(|line| Line::new(line.unwrap()))
-        .collect()
+/// Loads lines of the navigation subsystem from the given input.
+pub fn load<R: BufRead>(reader: R) -> Result<Vec<Line>> {
+    parse_lines(reader)
 }
 
 /// Returns the sum of syntax scores for lines that have mismatched closing characters.
-fn syntax_score(lines: &Vec<Line>) -> i32 {
+pub fn syntax_score(lines: &[Line]) -> i32 {
     lines.iter().flat_map(|line| line.syntax_score()).sum()
 }
 
 /// Returns the middle autocomplete score for incomplete lines.
-fn autocomplete_score(lines: &Vec<Line>) -> i64 {
+pub fn autocomplete_score(lines: &[Line]) -> Result<i64> {
     let scores = lines.iter()
         .flat_map(|line| line.autocomplete_score())
         .sorted()
         .collect::<Vec<i64>>();
 
-    scores[scores.len() / 2]
+    if scores.is_empty() {
+        return Err(Error::no_solution("There aren't any incomplete lines to autocomplete."));
+    }
+
+    Ok(scores[scores.len() / 2])
 }
 
-struct Line {
+/// Line is a line of the navigation subsystem, made of chunks like `[(<>)]`.
+pub struct Line {
     line: String
 }
 
-impl Line {
-    fn new(line: String) -> Self {
-        Self { line }
+impl FromStr for Line {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        // Lines only contain chunk delimiters.
+        if let Some((i, c)) = s.char_indices().find(|(_, c)| !"()[]{}<>".contains(*c)) {
+            return Err(ParseError::new(i + 1, format!("expected one of ()[]{{}}<>, found '{}'", c)));
+        }
+
+        Ok(Line { line: s.to_string() })
     }
+}
 
+impl Line {
     /// Returns the syntax error score if this line is corrupted, or 0 otherwise.   A line has a
     /// syntax error if a chunk opens and closes with mismatched characters.  Closing with
     /// `)` = 3 points, `]` = 57, `}` = 1197, and `>` = 25137.
-    fn syntax_score(&self) -> Option<i32> {
+    pub fn syntax_score(&self) -> Option<i32> {
         let scores = vec![
             (')', 3),
             (']', 57),
@@ -78,7 +104,10 @@ impl Line {
         None
     }
 
-    fn autocomplete_score(&self) -> Option<i64> {
+    /// Returns the autocomplete score if this line is incomplete, or None if it's corrupted.
+    /// The score is built from the closing characters needed to complete the line, where
+    /// `)` = 1 point, `]` = 2, `}` = 3, and `>` = 4.
+    pub fn autocomplete_score(&self) -> Option<i64> {
         let scores = vec![
             (')', 1),
             (']', 2),
@@ -118,7 +147,12 @@ impl Line {
 
 #[test]
 fn score_sample_file() {
-    let lines = load("input/day10_sample.txt");
+    let lines = load(std::fs::read_to_string("input/day10_sample.txt").unwrap().as_bytes()).unwrap();
     assert_eq!(26397, syntax_score(&lines));
-    assert_eq!(288957, autocomplete_score(&lines));
+    assert_eq!(288957, autocomplete_score(&lines).unwrap());
+}
+
+#[test]
+fn parse_invalid_line() {
+    assert_eq!(Err(ParseError::new(3, "expected one of ()[]{}<>, found 'x'")), "[(x)]".parse::<Line>().map(|line| line.line));
 }
\ No newline at end of file
@@ -1,58 +1,68 @@
 use std::collections::HashSet;
-use std::fmt::{Debug, Formatter};
-use std::fs::File;
-use std::io::{BufRead, BufReader};
+use std::io::BufRead;
 
-#[allow(dead_code)]
-pub fn solution() {
-    let map = Map::load("input/day11.txt");
+use crate::error::Result;
+use crate::grid::{Grid, Point};
+use crate::solution::Solution;
 
-    println!("Part 1: {}", map.clone().step_times(100));
-    println!("Part 2: {}", map.clone().all_flash())
+/// Day 11: Dumbo Octopus.
+pub struct Day11;
+
+impl Solution for Day11 {
+    type Puzzle = Map;
+    type Part1 = usize;
+    type Part2 = usize;
+
+    fn load<R: BufRead>(reader: R) -> Result<Self::Puzzle> {
+        Map::load(reader)
+    }
+
+    fn part1(map: &Self::Puzzle) -> Result<Self::Part1> {
+        Ok(map.clone().step_times(100))
+    }
+
+    fn part2(map: &Self::Puzzle) -> Result<Self::Part2> {
+        Ok(map.clone().all_flash())
+    }
 }
 
+/// Map holds the energy level of each octopus in the cavern.
 #[derive(Debug, Clone)]
-struct Map {
-    levels: Vec<Vec<i32>>
+pub struct Map {
+    levels: Grid<i32>
 }
 
 impl Map {
-    fn load(filename: &str) -> Self {
-        let f = File::open(filename).unwrap();
-        let f = BufReader::new(f);
-
-        let levels = f.lines()
-            .map(|line| line.unwrap().chars().map(|c| c as i32 - '0' as i32).collect())
-            .collect();
+    /// Loads a Map from the given input, which has a digit for each octopus' energy level.
+    pub fn load<R: BufRead>(reader: R) -> Result<Self> {
+        let levels = Grid::digits(reader)?;
 
-        Map { levels }
+        Ok(Map { levels })
     }
 
     /// Simulates a step, returning the number of flashes this round.
-    fn step(&mut self) -> usize {
+    pub fn step(&mut self) -> usize {
         // Any octopus with an energy level > 9 flashes
         let mut to_spread = Vec::new();
         let mut flashed = HashSet::new();
 
         // Energy level of each octopus increases by 1
-        for row in 0..self.levels.len() {
-            for col in 0..self.levels[row].len() {
-                self.levels[row][col] += 1;
+        for loc in self.levels.points() {
+            self.levels[loc] += 1;
 
-                if self.levels[row][col] > 9 {
-                    flashed.insert(Location::new(row, col));
-                    to_spread.push(Location::new(row, col));
-                }
+            if self.levels[loc] > 9 {
+                flashed.insert(loc);
+                to_spread.push(loc);
             }
         }
 
         // Flashing octopi increase the level of neighbors by 1, which can trigger other flashes
         while let Some(octopus) = to_spread.pop() {
-            for neighbor in self.neighbors(&octopus) {
+            for neighbor in self.levels.neighbors8(octopus).collect::<Vec<Point>>() {
                 if !flashed.contains(&neighbor) {
-                    self.levels[neighbor.row][neighbor.col] += 1;
+                    self.levels[neighbor] += 1;
 
-                    if self.levels[neighbor.row][neighbor.col] > 9 {
+                    if self.levels[neighbor] > 9 {
                         flashed.insert(neighbor);
                         to_spread.push(neighbor);
                     }
@@ -61,21 +71,21 @@ impl Map {
         }
 
         // Any octopus that flashed has its energy level set to 0.
-        for octopus in &flashed {
-            self.levels[octopus.row][octopus.col] = 0;
+        for &octopus in &flashed {
+            self.levels[octopus] = 0;
         }
 
         flashed.len()
     }
 
     /// Simulates the given number of steps, returning the number of octopodes that flash.
-    fn step_times(&mut self, steps: usize) -> usize {
+    pub fn step_times(&mut self, steps: usize) -> usize {
         (0 .. steps).map(|_| self.step()).sum()
     }
 
     /// Returns the first step when all octopuses flash.
-    fn all_flash(&mut self) -> usize {
-        let all_count = self.levels.len() * self.levels[0].len();
+    pub fn all_flash(&mut self) -> usize {
+        let all_count = self.levels.len();
         let mut step = 1;
 
         while self.step() < all_count {
@@ -84,53 +94,11 @@ impl Map {
 
         step
     }
-
-    /// Returns a list of valid neighbors around the given location.
-    fn neighbors(&self, loc: &Location) -> Vec<Location> {
-        let mut neighbors = Vec::new();
-
-        for add_row in -1 ..= 1 {
-            for add_col in -1 ..= 1 {
-                let valid_neighbor = (add_row != 0 || add_col != 0)
-                    && (add_row > -1 || loc.row > 0)
-                    && (add_col > -1 || loc.col > 0)
-                    && (add_row < 1 || loc.row < self.levels.len() - 1)
-                    && (add_col < 1 || loc.col < self.levels[loc.row].len() - 1);
-
-                if valid_neighbor {
-                    neighbors.push(Location::new(
-                        (loc.row as i32 + add_row) as usize,
-                        (loc.col as i32 + add_col) as usize,
-                    ));
-                }
-            }
-        }
-
-        neighbors
-    }
-}
-
-#[derive(Eq, PartialEq, Hash, Copy, Clone)]
-struct Location {
-    row: usize,
-    col: usize,
-}
-
-impl Debug for Location {
-    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
-        write!(f, "({}, {})", self.row, self.col)
-    }
-}
-
-impl Location {
-    fn new(row: usize, col: usize) -> Self {
-        Location { row, col }
-    }
 }
 
 #[test]
 fn test_sample() {
-    let map = Map::load("input/day11_sample.txt");
+    let map = Map::load(std::fs::read_to_string("input/day11_sample.txt").unwrap().as_bytes()).unwrap();
 
     assert_eq!(1656, map.clone().step_times(100));
     assert_eq!(195, map.clone().all_flash());
@@ -1,50 +1,84 @@
 use std::collections::{HashMap, HashSet};
-use std::fs::File;
-use std::io::{BufRead, BufReader};
+use std::io::BufRead;
 
-#[allow(dead_code)]
-pub fn solution() {
-    let cave_system = CaveSystem::load("input/day12.txt");
+use crate::error::{column_of, Error, ParseError, Result};
+use crate::solution::Solution;
 
-    println!("Part 1: {}", cave_system.paths(false));
-    println!("Part 2: {}", cave_system.paths(true));
+/// Day 12: Passage Pathing.
+pub struct Day12;
+
+impl Solution for Day12 {
+    type Puzzle = CaveSystem;
+    type Part1 = usize;
+    type Part2 = usize;
+
+    fn load<R: BufRead>(reader: R) -> Result<Self::Puzzle> {
+        CaveSystem::load(reader)
+    }
+
+    fn part1(cave_system: &Self::Puzzle) -> Result<Self::Part1> {
+        cave_system.paths(false)
+    }
+
+    fn part2(cave_system: &Self::Puzzle) -> Result<Self::Part2> {
+        cave_system.paths(true)
+    }
 }
 
+/// CaveSystem maps each cave to the caves it connects to.
 #[derive(Debug)]
-struct CaveSystem {
+pub struct CaveSystem {
     caves: HashMap<String, HashSet<String>>
 }
 
 impl CaveSystem {
-    /// Loads a cave system from the given file, which describes connected
+    /// Loads a cave system from the given input, which describes connected
     /// caves like 'start-A'.
-    fn load(filename: &str) -> CaveSystem {
-        let f = File::open(filename).unwrap();
-        let f = BufReader::new(f);
-
+    pub fn load<R: BufRead>(reader: R) -> Result<CaveSystem> {
         let mut caves: HashMap<String, HashSet<String>> = HashMap::new();
 
-        for line in f.lines() {
-            let cave_connection = line.unwrap()
-                .split("-")
-                .map(|s| s.to_string())
-                .collect::<Vec<String>>();
+        for (i, line) in reader.lines().enumerate() {
+            let line = line?;
+            let line = line.trim();
+            if line.is_empty() {
+                continue;
+            }
+
+            let error = |column: usize, message: String| ParseError::new(column, message).on_line(i + 1);
+
+            let (cave_a, cave_b) = line.split_once('-')
+                .ok_or_else(|| error(1, format!("expected a connection like 'start-A', found '{}'", line)))?;
+
+            for cave in [cave_a, cave_b] {
+                if cave.is_empty() || !cave.chars().all(|c| c.is_ascii_alphabetic()) {
+                    return Err(error(column_of(line, cave), format!("expected a cave name made of letters, found '{}'", cave)).into());
+                }
+            }
+
+            // Paths could bounce between two connected big caves forever.
+            if is_big(cave_a) && is_big(cave_b) {
+                return Err(error(1, format!("big caves '{}' and '{}' can't be connected", cave_a, cave_b)).into());
+            }
 
-            let cave_a = cave_connection[0].clone();
-            let cave_b = cave_connection[1].clone();
+            let cave_a = cave_a.to_string();
+            let cave_b = cave_b.to_string();
 
             caves.entry(cave_a.clone()).or_default().insert(cave_b.clone());
             caves.entry(cave_b.clone()).or_default().insert(cave_a.clone());
         }
 
-        CaveSystem { caves }
+        Ok(CaveSystem { caves })
     }
 
     /// Returns the number of paths through this cave system.
     /// Paths start at 'start', end at 'end', and can travel through capitalized
     /// caves more than once.  If small_twice is true, the sub can travel through a single
     /// lowercase cave (other than start or end) twice.
-    fn paths(&self, small_twice: bool) -> usize {
+    pub fn paths(&self, small_twice: bool) -> Result<usize> {
+        if !self.caves.contains_key("start") {
+            return Err(Error::no_solution("There isn't a start cave."));
+        }
+
         #[derive(Debug)]
         struct Explore {
             visited: HashSet<String>,
@@ -66,8 +100,7 @@ impl CaveSystem {
             /// can be visited more than once.
             fn can_visit(&self, cave: &str, small_twice: bool) -> bool {
                 // Can visit big caves or caves we've never visited before any number of times.
-                let is_big = cave.chars().next().unwrap().is_uppercase();
-                if is_big || !self.visited.contains(cave) {
+                if is_big(cave) || !self.visited.contains(cave) {
                     return true;
                 }
 
@@ -81,7 +114,7 @@ impl CaveSystem {
 
             /// Returns a new Explore with the sub at the given cave.
             fn visit(&self, cave: &str) -> Self {
-                let is_small_twice = cave.chars().next().unwrap().is_lowercase()
+                let is_small_twice = !is_big(cave)
                     && "start" != cave
                     && "end" != cave
                     && self.visited.contains(cave);
@@ -109,24 +142,52 @@ impl CaveSystem {
                 paths += 1;
             } else {
                 for neighbor in &self.caves[&explore.at] {
-                    if explore.can_visit(&neighbor, small_twice) {
-                        to_explore.push(explore.visit(&neighbor));
+                    if explore.can_visit(neighbor, small_twice) {
+                        to_explore.push(explore.visit(neighbor));
                     }
                 }
             }
         }
 
-        paths
+        Ok(paths)
     }
 }
 
+/// Returns whether the given cave is big, which means it has a capitalized name.
+fn is_big(cave: &str) -> bool {
+    cave.chars().next().is_some_and(|c| c.is_uppercase())
+}
+
+#[test]
+fn load_from_text() {
+    let cave_system = CaveSystem::load("start-A\nstart-b\nA-c\nA-b\nb-d\nA-end\nb-end\n".as_bytes()).unwrap();
+
+    assert_eq!(10, cave_system.paths(false).unwrap());
+    assert_eq!(36, cave_system.paths(true).unwrap());
+}
+
+#[test]
+fn load_invalid() {
+    match CaveSystem::load("start-A\nA-\n".as_bytes()) {
+        Err(Error::Parse(e)) => assert_eq!(ParseError { line: 2, column: 3, message: "expected a cave name made of letters, found ''".to_string() }, e),
+        other => panic!("Expected a parse error, got {:?}", other),
+    }
+
+    match CaveSystem::load("start-A\nA-B\n".as_bytes()) {
+        Err(Error::Parse(e)) => assert_eq!(ParseError { line: 2, column: 1, message: "big caves 'A' and 'B' can't be connected".to_string() }, e),
+        other => panic!("Expected a parse error, got {:?}", other),
+    }
+
+    assert!(matches!(CaveSystem::load("a-end\n".as_bytes()).unwrap().paths(false), Err(Error::NoSolution(_))));
+}
+
 #[test]
 fn paths_samples() {
-    assert_eq!(10, CaveSystem::load("input/day12_sample.txt").paths(false));
-    assert_eq!(19, CaveSystem::load("input/day12_sample2.txt").paths(false));
-    assert_eq!(226, CaveSystem::load("input/day12_sample3.txt").paths(false));
+    assert_eq!(10, CaveSystem::load(std::fs::read_to_string("input/day12_sample.txt").unwrap().as_bytes()).unwrap().paths(false).unwrap());
+    assert_eq!(19, CaveSystem::load(std::fs::read_to_string("input/day12_sample2.txt").unwrap().as_bytes()).unwrap().paths(false).unwrap());
+    assert_eq!(226, CaveSystem::load(std::fs::read_to_string("input/day12_sample3.txt").unwrap().as_bytes()).unwrap().paths(false).unwrap());
 
-    assert_eq!(36, CaveSystem::load("input/day12_sample.txt").paths(true));
-    assert_eq!(103, CaveSystem::load("input/day12_sample2.txt").paths(true));
-    assert_eq!(3509, CaveSystem::load("input/day12_sample3.txt").paths(true));
+    assert_eq!(36, CaveSystem::load(std::fs::read_to_string("input/day12_sample.txt").unwrap().as_bytes()).unwrap().paths(true).unwrap());
+    assert_eq!(103, CaveSystem::load(std::fs::read_to_string("input/day12_sample2.txt").unwrap().as_bytes()).unwrap().paths(true).unwrap());
+    assert_eq!(3509, CaveSystem::load(std::fs::read_to_string("input/day12_sample3.txt").unwrap().as_bytes()).unwrap().paths(true).unwrap());
 }
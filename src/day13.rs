@@ -1,22 +1,35 @@
 use std::collections::VecDeque;
-use std::fmt::{Debug, Formatter};
-use std::fs::File;
-use std::io::{BufRead, BufReader};
+use std::fmt::{Display, Formatter};
+use std::io::BufRead;
 use std::str::FromStr;
 use itertools::Itertools;
 
-#[allow(dead_code)]
-pub fn solution() {
-    let mut paper = Paper::load("input/day13.txt");
+use crate::error::{parse_num, Error, ParseError, Result};
+use crate::solution::Solution;
 
-    println!("Part 1: {}", paper.fold_once());
+/// Day 13: Transparent Origami.
+pub struct Day13;
 
-    paper.fold_all();
-    println!("Part 2 (read letters):\n{:?}", paper);
-}
+impl Solution for Day13 {
+    type Puzzle = Paper;
+    type Part1 = usize;
+    type Part2 = Paper;
+
+    fn load<R: BufRead>(reader: R) -> Result<Self::Puzzle> {
+        Paper::load(reader)
+    }
+
+    fn part1(paper: &Self::Puzzle) -> Result<Self::Part1> {
+        paper.clone().fold_once()
+    }
 
-#[derive(Debug)]
-enum ParseErr {}
+    fn part2(paper: &Self::Puzzle) -> Result<Self::Part2> {
+        let mut paper = paper.clone();
+        paper.fold_all();
+
+        Ok(paper)
+    }
+}
 
 #[derive(Debug, Eq, PartialEq, Hash, Clone)]
 struct Dot {
@@ -31,79 +44,83 @@ impl Dot {
 }
 
 impl FromStr for Dot {
-    type Err = ParseErr;
+    type Err = ParseError;
 
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
         // Dot looks like '6,10'
-        let mut nums = s.split(',').map(|i| i.parse().unwrap());
+        let (x, y) = s.split_once(',')
+            .ok_or_else(|| ParseError::new(1, format!("expected a dot like '6,10', found '{}'", s)))?;
+
         Ok(Dot {
-            x: nums.next().unwrap(),
-            y: nums.next().unwrap(),
+            x: parse_num(s, x)?,
+            y: parse_num(s, y)?,
         })
     }
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 enum Fold {
     X(i32),
     Y(i32),
 }
 
 impl FromStr for Fold {
-    type Err = ParseErr;
+    type Err = ParseError;
 
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
         // Fold looks like 'fold along y=7'
-        let eq = s.find("=").unwrap();
+        let instruction = s.strip_prefix("fold along ")
+            .ok_or_else(|| ParseError::new(1, format!("expected a fold like 'fold along y=7', found '{}'", s)))?;
 
-        let index = s[eq+1..].parse().unwrap();
-
-        if s.contains('x') {
-            Ok(Fold::X(index))
-        } else {
-            Ok(Fold::Y(index))
+        match instruction.split_once('=') {
+            Some(("x", index)) => Ok(Fold::X(parse_num(s, index)?)),
+            Some(("y", index)) => Ok(Fold::Y(parse_num(s, index)?)),
+            _ => Err(ParseError::at(s, instruction, format!("expected x= or y=, found '{}'", instruction))),
         }
     }
 }
 
-struct Paper {
+/// Paper is a sheet of transparent paper with dots on it, and the instructions for folding it.
+#[derive(Clone)]
+pub struct Paper {
     dots: Vec<Dot>,
     folds: VecDeque<Fold>,
 }
 
 impl Paper {
-    /// Loads paper from the given file.  The file contains points like '6,10' where dots are
+    /// Loads paper from the given input.  The input contains points like '6,10' where dots are
     /// visible, followed by fold instructions like 'fold along y=7'.
-    fn load(filename: &str) -> Self {
-        let f = File::open(filename).unwrap();
-        let f = BufReader::new(f);
-        let lines = f.lines();
-
+    pub fn load<R: BufRead>(reader: R) -> Result<Self> {
         let mut dots = Vec::new();
         let mut folds = VecDeque::new();
         let mut parsing_dots = true;
 
-        for maybe_line in lines {
-            let line = maybe_line.unwrap();
+        for (i, maybe_line) in reader.lines().enumerate() {
+            let line = maybe_line?;
+            let on_line = |e: ParseError| e.on_line(i + 1);
+
             if line.is_empty() {
                 parsing_dots = false;
             } else if parsing_dots {
-                dots.push(line.parse().unwrap());
+                dots.push(line.parse().map_err(on_line)?);
             } else {
-                folds.push_back(line.parse().unwrap());
+                folds.push_back(line.parse().map_err(on_line)?);
             }
         }
 
-        Paper { dots, folds }
+        Ok(Paper { dots, folds })
     }
 
     /// Follows the next fold instruction, returning the number of dots that are visible.
-    fn fold_once(&mut self) -> usize {
+    pub fn fold_once(&mut self) -> Result<usize> {
+        let fold = self.folds.pop_front()
+            .ok_or_else(|| Error::no_solution("There aren't any folds left."))?;
+
         // Fold the paper.
-        match self.folds.pop_front().unwrap() {
+        match fold {
             Fold::X(index) => {
                 // all dots to the right of the index are folded left.
-                for mut dot in &mut self.dots {
+                for dot in &mut self.dots {
                     if dot.x > index {
                         dot.x -= 2 * (dot.x - index);
                     }
@@ -111,7 +128,7 @@ impl Paper {
             }
             Fold::Y(index) => {
                 // all dots below the index are folded up.
-                for mut dot in &mut self.dots {
+                for dot in &mut self.dots {
                     if dot.y > index {
                         dot.y -= 2 * (dot.y - index);
                     }
@@ -123,17 +140,16 @@ impl Paper {
         self.dots = self.dots.iter().unique().cloned().collect();
 
         // return the number of dots that are still visible.
-        self.dots.len()
+        Ok(self.dots.len())
     }
 
-    fn fold_all(&mut self) {
-        while !self.folds.is_empty() {
-            self.fold_once();
-        }
+    /// Follows all of the remaining fold instructions.
+    pub fn fold_all(&mut self) {
+        while self.fold_once().is_ok() {}
     }
 }
 
-impl Debug for Paper {
+impl Display for Paper {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
         let (max_x, max_y) = self.dots.iter()
             .fold((0, 0), |(x_acc, y_acc), dot| (x_acc.max(dot.x), y_acc.max(dot.y)));
@@ -156,6 +172,12 @@ impl Debug for Paper {
 
 #[test]
 fn test_sample() {
-    let mut paper = Paper::load("input/day13_sample.txt");
-    assert_eq!(17, paper.fold_once());
+    let mut paper = Paper::load(std::fs::read_to_string("input/day13_sample.txt").unwrap().as_bytes()).unwrap();
+    assert_eq!(17, paper.fold_once().unwrap());
+}
+
+#[test]
+fn parse_invalid() {
+    assert_eq!(Err(ParseError::new(3, "expected a number, found 'x'")), "6,x".parse::<Dot>());
+    assert_eq!(Err(ParseError::new(12, "expected x= or y=, found 'z=7'")), "fold along z=7".parse::<Fold>().map(|_| ()));
 }
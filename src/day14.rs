@@ -1,53 +1,82 @@
 use std::collections::HashMap;
-use std::fs::File;
-use std::io::{BufRead, BufReader};
+use std::io::BufRead;
 
-#[allow(dead_code)]
-pub fn solution() {
-    let poly = Polymerization::load("input/day14.txt");
+use crate::error::{Error, ParseError, Result};
+use crate::solution::Solution;
 
-    println!("Part 1: {}", poly.score(10));
-    println!("Part 2: {}", poly.score(40));
+/// Day 14: Extended Polymerization.
+pub struct Day14;
+
+impl Solution for Day14 {
+    type Puzzle = Polymerization;
+    type Part1 = usize;
+    type Part2 = usize;
+
+    fn load<R: BufRead>(reader: R) -> Result<Self::Puzzle> {
+        Polymerization::load(reader)
+    }
+
+    fn part1(poly: &Self::Puzzle) -> Result<Self::Part1> {
+        poly.score(10)
+    }
+
+    fn part2(poly: &Self::Puzzle) -> Result<Self::Part2> {
+        poly.score(40)
+    }
 }
 
+/// Polymerization is a polymer template and the pair insertion rules that grow it.
 #[derive(Debug)]
-struct Polymerization {
+pub struct Polymerization {
     template: Vec<char>,
     rules: HashMap<(char, char), char>,
 }
 
 impl Polymerization {
-    /// Loads a Polymerization from the given file, which contains a polymer template
+    /// Loads a Polymerization from the given input, which contains a polymer template
     /// followed by rules.
-    fn load(filename: &str) -> Self {
-        let f = File::open(filename).unwrap();
-        let f = BufReader::new(f);
-        let mut lines = f.lines();
+    pub fn load<R: BufRead>(reader: R) -> Result<Self> {
+        let mut lines = reader.lines();
 
         // Template.
-        let template: Vec<char> = lines.next().unwrap().unwrap().chars().collect();
-
-        // Blank line.
-        let _ = lines.next();
+        let template: Vec<char> = lines.next()
+            .ok_or_else(|| ParseError::new(1, "expected a polymer template"))??
+            .trim_end()
+            .chars()
+            .collect();
+
+        if template.is_empty() {
+            return Err(ParseError::new(1, "expected a polymer template").into());
+        }
 
-        // Rules
+        // Rules follow a blank line.
         let mut rules = HashMap::new();
-        for next_line in lines {
+        for (i, next_line) in lines.enumerate().skip(1) {
             // Rules look like 'NV -> S'
-            let line = next_line.unwrap();
-            let mut split = line.split(" -> ");
-            let mut chars = split.next().unwrap().chars();
+            let line = next_line?;
+            let line = line.trim_end();
+            if line.is_empty() {
+                continue;
+            }
+
+            let rule = match line.split_once(" -> ") {
+                Some((pair, element)) if pair.chars().count() == 2 && element.chars().count() == 1 =>
+                    Some((pair.chars().collect::<Vec<char>>(), element.chars().next().unwrap())),
+                _ => None,
+            };
+
+            let (pair, element) = rule.ok_or_else(|| ParseError::new(1, format!("expected a rule like 'NV -> S', found '{}'", line))
+                .on_line(i + 2))?;
 
-            let key = (chars.next().unwrap(), chars.next().unwrap());
-            rules.insert(key, split.next().unwrap().chars().next().unwrap());
+            rules.insert((pair[0], pair[1]), element);
         }
 
-        Polymerization { template, rules }
+        Ok(Polymerization { template, rules })
     }
 
     /// Performs the given number of insertions and returns the quantity of the most common element
     /// minus the quantity of the least common element.
-    fn score(&self, times: usize) -> usize {
+    pub fn score(&self, times: usize) -> Result<usize> {
         let mut count: HashMap<(char, char), usize> = HashMap::new();
 
         // Load the template into count.
@@ -60,7 +89,8 @@ impl Polymerization {
             let mut new_count = HashMap::new();
 
             for ((a, b), num) in count {
-                let letter = self.rules[&(a, b)];
+                let letter = *self.rules.get(&(a, b))
+                    .ok_or_else(|| Error::no_solution(format!("There isn't a rule for the pair '{}{}'.", a, b)))?;
                 *new_count.entry((a, letter)).or_default() += num;
                 *new_count.entry((letter, b)).or_default() += num;
             }
@@ -81,14 +111,21 @@ impl Polymerization {
         letter_count.values_mut().for_each(|num| *num /= 2);
 
         // Score is the most common element - least common element.
-        letter_count.values().max().unwrap() - letter_count.values().min().unwrap()
+        Ok(letter_count.values().max().unwrap() - letter_count.values().min().unwrap())
     }
 }
 
 #[test]
 fn test_sample() {
-    let poly = Polymerization::load("input/day14_sample.txt");
+    let poly = Polymerization::load(std::fs::read_to_string("input/day14_sample.txt").unwrap().as_bytes()).unwrap();
+
+    assert_eq!(1588, poly.score(10).unwrap());
+    assert_eq!(2188189693529, poly.score(40).unwrap())
+}
+
+#[test]
+fn test_missing_rule() {
+    let poly = Polymerization::load("NNC\n\nNN -> C\n".as_bytes()).unwrap();
 
-    assert_eq!(1588, poly.score(10));
-    assert_eq!(2188189693529, poly.score(40))
+    assert!(matches!(poly.score(1), Err(Error::NoSolution(_))));
 }
@@ -1,73 +1,69 @@
 use std::cmp::Ordering;
 use std::collections::{BinaryHeap, HashMap};
 use std::fmt::{Debug, Formatter};
-use std::fs::File;
-use std::io::{BufRead, BufReader};
+use std::io::BufRead;
 
-#[allow(dead_code)]
-pub fn solution() {
-    let map = Map::load("input/day15.txt");
-    println!("Part 1: {}", map.total_risk());
+use crate::error::{Error, Result};
+use crate::grid::{Grid, Point};
+use crate::solution::Solution;
 
-    let big_map = map.expand();
-    println!("Part 2: {}", big_map.total_risk());
+/// Day 15: Chiton.
+pub struct Day15;
+
+impl Solution for Day15 {
+    type Puzzle = Map;
+    type Part1 = i32;
+    type Part2 = i32;
+
+    fn load<R: BufRead>(reader: R) -> Result<Self::Puzzle> {
+        Map::load(reader)
+    }
+
+    fn part1(map: &Self::Puzzle) -> Result<Self::Part1> {
+        map.total_risk()
+    }
+
+    fn part2(map: &Self::Puzzle) -> Result<Self::Part2> {
+        map.expand().total_risk()
+    }
 }
 
-struct Map {
-    risk: Vec<Vec<i32>>
+/// Map holds the risk level of each position in the cave.
+pub struct Map {
+    risk: Grid<i32>
 }
 
 impl Map {
-    /// Loads a HeightMap from the given file.
-    fn load(filename: &str) -> Self {
-        let f = File::open(filename).unwrap();
-        let f = BufReader::new(f);
-
-        let risk = f.lines()
-            .map(|line| line.unwrap().chars().map(|c| c as i32 - '0' as i32).collect())
-            .collect();
+    /// Loads a Map from the given input, which has a digit for each position's risk.
+    pub fn load<R: BufRead>(reader: R) -> Result<Self> {
+        let risk = Grid::digits(reader)?;
 
-        Map { risk }
+        Ok(Map { risk })
     }
 
     /// Returns a new expanded map with this map as a tile in a 5x5 grid.
-    fn expand(&self) -> Self {
-        let rows = self.risk.len();
-        let cols = self.risk[0].len();
-
-        let mut expanded_risk = vec![vec![0; rows * 5]; cols * 5];
-
-        for row in 0..rows {
-            for col in 0..cols {
-                for expand_row in 0..5 {
-                    for expand_col in 0..5 {
-                        // Each tile's risk is 1 higher than the tile immediately up or to the left.
-                        // Risk levels above 9 wrap back around to 1.
-                        let mut new_risk = self.risk[row][col] + expand_row as i32 + expand_col as i32;
-                        if new_risk > 9 {
-                            new_risk = new_risk % 10 + 1;
-                        }
-
-                        expanded_risk[row + expand_row * rows][col + expand_col * cols] = new_risk;
-                    }
-                }
-            }
-        }
+    pub fn expand(&self) -> Self {
+        let rows = self.risk.rows();
+        let cols = self.risk.cols();
 
-        Map { risk: expanded_risk }
-    }
+        let risk = Grid::from_fn(rows * 5, cols * 5, |pos| {
+            // Each tile's risk is 1 higher than the tile immediately up or to the left.
+            // Risk levels above 9 wrap back around to 1.
+            let tile_risk = self.risk[Point::new(pos.row % rows, pos.col % cols)];
+            let new_risk = tile_risk + (pos.row / rows) as i32 + (pos.col / cols) as i32;
+
+            if new_risk > 9 { new_risk % 10 + 1 } else { new_risk }
+        });
 
-    /// Returns the risk at the given position.
-    fn risk(&self, pos: &Position) -> i32 {
-        self.risk[pos.row][pos.col]
+        Map { risk }
     }
 
     /// Finds a path from the top left corner to the bottom right with
     /// the lowest risk.
-    fn total_risk(&self) -> i32 {
+    pub fn total_risk(&self) -> Result<i32> {
         // A* search to find the path with the lowest risk.
-        let start = ToExplore::start(&self);
-        let end = Position::end(&self);
+        let start = ToExplore::start(self);
+        let end = Point::new(self.risk.rows() - 1, self.risk.cols() - 1);
 
         // Nodes we still need to explore.
         let mut explore = BinaryHeap::new();
@@ -80,35 +76,27 @@ impl Map {
         // Explore nodes with the lowest heuristic cost until we reach the end.
         while let Some(node) = explore.pop() {
             if node.pos == end {
-                return node.risk;
+                return Ok(node.risk);
             }
 
-            for neighbor in node.pos.neighbors(&self) {
-                let neighbor_risk = node.risk + self.risk(&neighbor);
+            for neighbor in self.risk.neighbors4(node.pos) {
+                let neighbor_risk = node.risk + self.risk[neighbor];
                 let current_risk = *pos_risk.get(&neighbor).unwrap_or(&i32::MAX);
 
                 if neighbor_risk < current_risk {
                     pos_risk.insert(neighbor, neighbor_risk);
-                    explore.push(node.to(neighbor, &self));
+                    explore.push(node.to(neighbor, self));
                 }
             }
         }
 
-        panic!("No path found.")
+        Err(Error::no_solution("No path found."))
     }
 }
 
 impl Debug for Map {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
-        for row in &self.risk {
-            for value in row {
-                write!(f, "{}", value)?;
-            }
-
-            writeln!(f)?;
-        }
-
-        Ok(())
+        write!(f, "{}", self.risk)
     }
 }
 
@@ -119,7 +107,7 @@ impl Debug for Map {
 #[derive(Debug, Eq, PartialEq, Clone)]
 struct ToExplore {
     /// Position on the map.
-    pos: Position,
+    pos: Point,
     /// Total actual risk from start to pos.
     risk: i32,
     /// Best guess at the risk from start to end traveling through this position.
@@ -130,7 +118,7 @@ impl ToExplore {
     /// Returns a new `ToExplore` at the starting square 0,0.  The starting square is never
     /// entered, so it's risk doesn't count.
     fn start(map: &Map) -> Self {
-        let pos = Position::new(0, 0);
+        let pos = Point::new(0, 0);
 
         ToExplore {
             pos,
@@ -140,8 +128,8 @@ impl ToExplore {
     }
 
     /// Returns a new `ToExplore` that travels from this position to the given neighbor.
-    fn to(&self, pos: Position, map: &Map) -> Self {
-        let risk = self.risk + map.risk(&pos);
+    fn to(&self, pos: Point, map: &Map) -> Self {
+        let risk = self.risk + map.risk[pos];
         let cost = risk + Self::heuristic_cost(&pos, map);
 
         ToExplore { pos, risk, cost }
@@ -149,8 +137,8 @@ impl ToExplore {
 
     /// Returns the heuristic cost from the given position to the end.
     /// All squares have 1 cost.
-    fn heuristic_cost(pos: &Position, map: &Map) -> i32 {
-        (map.risk.len() - pos.row - 1) as i32 + (map.risk[0].len() - pos.col - 1) as i32
+    fn heuristic_cost(pos: &Point, map: &Map) -> i32 {
+        (map.risk.rows() - pos.row - 1) as i32 + (map.risk.cols() - pos.col - 1) as i32
     }
 }
 
@@ -166,52 +154,11 @@ impl PartialOrd for ToExplore {
     }
 }
 
-/// Position represents a row and column on the map.
-#[derive(Eq, PartialEq, Hash, Copy, Clone)]
-struct Position {
-    row: usize,
-    col: usize,
-}
-
-impl Position {
-    /// Creates a new Position at the given row and col.
-    fn new(row: usize, col: usize) -> Self {
-        Position { row, col }
-    }
-
-    /// Returns valid neighbors around the given position on the map.  Neighbors are up, down,
-    /// left, and right - diagonals are not valid.
-    fn neighbors<'a>(&'a self, map: &'a Map) -> impl Iterator<Item=Position> + 'a {
-        [(-1, 0), (1, 0), (0, -1), (0, 1)].into_iter()
-            .filter(|&(add_row, add_col)|
-                (add_row >= 0 || self.row > 0)
-                    && (add_col >= 0 || self.col > 0)
-                    && (add_row <= 0 || self.row < map.risk.len() - 1)
-                    && (add_col <= 0 || self.col < map.risk[0].len() - 1)
-            )
-            .map(|(add_row, add_col)| Position::new(
-                (self.row as i32 + add_row) as usize,
-                (self.col as i32 + add_col) as usize,
-            ))
-    }
-
-    /// Returns the end position on the map.
-    fn end(map: &Map) -> Self {
-        Position::new(map.risk.len() - 1, map.risk[0].len() - 1)
-    }
-}
-
-impl Debug for Position {
-    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
-        write!(f, "({}, {})", self.row, self.col)
-    }
-}
-
 #[test]
 fn test_sample() {
-    let map = Map::load("input/day15_sample.txt");
-    assert_eq!(40, map.total_risk());
+    let map = Map::load(std::fs::read_to_string("input/day15_sample.txt").unwrap().as_bytes()).unwrap();
+    assert_eq!(40, map.total_risk().unwrap());
 
     let big_map = map.expand();
-    assert_eq!(315, big_map.total_risk());
+    assert_eq!(315, big_map.total_risk().unwrap());
 }
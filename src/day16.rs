@@ -1,119 +1,168 @@
-use std::fs::File;
-use std::io::{BufRead, BufReader};
+use std::io::BufRead;
 
 use bitvec::prelude::*;
 
-#[allow(dead_code)]
-pub fn solution() {
-    let packet = load("input/day16.txt");
+use crate::error::{ParseError, Result};
+use crate::solution::Solution;
 
-    println!("Part 1: {}", packet.version_sum());
-    println!("Part 2: {}", packet.value());
-}
+/// Day 16: Packet Decoder.
+pub struct Day16;
+
+impl Solution for Day16 {
+    type Puzzle = Packet;
+    type Part1 = u64;
+    type Part2 = u64;
+
+    fn load<R: BufRead>(reader: R) -> Result<Self::Puzzle> {
+        load(reader)
+    }
 
-fn load(filename: &str) -> Packet {
-    let f = File::open(filename).unwrap();
-    let mut f = BufReader::new(f);
+    fn part1(packet: &Self::Puzzle) -> Result<Self::Part1> {
+        Ok(packet.version_sum())
+    }
+
+    fn part2(packet: &Self::Puzzle) -> Result<Self::Part2> {
+        Ok(packet.value())
+    }
+}
 
-    // Read hex digits out of the file.
+/// Loads a packet from the hex digits on the first line of the given reader.
+pub fn load<R: BufRead>(mut reader: R) -> Result<Packet> {
     let mut s = String::new();
-    f.read_line(&mut s).unwrap();
+    reader.read_line(&mut s)?;
 
-    Packet::parse(&s)
+    Ok(Packet::parse(s.trim_end())?)
 }
 
 /// Parses a packet out of the given bits that starts at index i, returning the packet and the
 /// next index.
-fn parse_packet(bits: &BitVec<Msb0, usize>, i: usize) -> (Packet, usize) {
+fn parse_packet(bits: &BitVec<Msb0, usize>, i: usize) -> std::result::Result<(Packet, usize), ParseError> {
     // First three bits are the version
-    let version = bits[i + 0..i + 3].load_be::<u8>();
+    let version = read_bits(bits, i, 3)? as u8;
 
     // Next three bits are the type id
-    let type_id = bits[i+3..i+6].load_be::<u8>();
+    let type_id = read_bits(bits, i + 3, 3)? as u8;
 
     if type_id == 4 {
         // Type 4 is a literal value
-        let (num, index) = parse_packet_number(bits, i + 6);
+        let (num, index) = parse_packet_number(bits, i + 6)?;
 
-        return (Packet::Literal { version, num }, index);
+        return Ok((Packet::Literal { version, num }, index));
     }
 
     // Other types are operators on one or more sub-packets.
     // The next bit is the length_type_id, which determines the number of sub-packets
-    let length_type_id = bits[i+6];
+    let length_type_id = read_bits(bits, i + 6, 1)? == 1;
 
     let mut packets = Vec::new();
     let mut index;
     if length_type_id {
         // The next 11 bits are a number that represents the number of sub-packets immediately
         // contained by this packet.
-        let length = bits[i + 7..i + 18].load_be::<u16>();
+        let length = read_bits(bits, i + 7, 11)?;
 
         index = i + 18;
         for _ in 0..length {
-            let (packet, new_index) = parse_packet(bits, index);
+            let (packet, new_index) = parse_packet(bits, index)?;
             packets.push(packet);
             index = new_index;
         }
     } else {
         // The next 15 bits are a number that represents the total length in bits of
         // sub-packets contained by this packet.
-        let length = bits[i + 7..i + 22].load_be::<u16>();
+        let length = read_bits(bits, i + 7, 15)?;
 
         index = i + 22;
-        while index < i + 22 + length as usize {
-            let (packet, new_index) = parse_packet(bits, index);
+        let end = index + length as usize;
+        while index < end {
+            let (packet, new_index) = parse_packet(bits, index)?;
             packets.push(packet);
             index = new_index;
         }
+
+        if index != end {
+            return Err(error_at(end, format!("sub-packets overran their length of {} bits", length)));
+        }
     };
 
-    (Packet::Operator { version, type_id, packets }, index)
+    // Comparisons have exactly two sub-packets, and the other operators need at least one.
+    let valid = match type_id {
+        5..=7 => packets.len() == 2,
+        _ => !packets.is_empty(),
+    };
+
+    if !valid {
+        return Err(error_at(i, format!("operator type {} can't have {} sub-packets", type_id, packets.len())));
+    }
+
+    Ok((Packet::Operator { version, type_id, packets }, index))
 }
 
 /// Parses a number starting at the given index in the bits.
-fn parse_packet_number(bits: &BitVec<Msb0, usize>, i: usize) -> (u64, usize) {
+fn parse_packet_number(bits: &BitVec<Msb0, usize>, i: usize) -> std::result::Result<(u64, usize), ParseError> {
     let mut index = i;
-    let mut num = 0;
+    let mut num: u64 = 0;
     loop {
         // Numbers are 5 bit chunks, where a 1 in the first bit means there's more chunks.
         // The remaining 4 bits are part of the number.
-        num = (num << 4) + bits[index + 1..index + 5].load_be::<u8>() as u64;
+        if num.leading_zeros() < 4 {
+            return Err(error_at(i, "literal value doesn't fit in 64 bits"));
+        }
+
+        num = (num << 4) + read_bits(bits, index + 1, 4)?;
         index += 5;
 
-        if !bits[index - 5] {
+        if read_bits(bits, index - 5, 1)? == 0 {
             break;
         }
     }
 
-    (num, index)
+    Ok((num, index))
+}
+
+/// Reads `len` bits starting at the given index as a big-endian number, or returns an error if
+/// the packet ends first.
+fn read_bits(bits: &BitVec<Msb0, usize>, i: usize, len: usize) -> std::result::Result<u64, ParseError> {
+    if i + len > bits.len() {
+        return Err(error_at(bits.len(), "packet ended unexpectedly"));
+    }
+
+    Ok(bits[i..i + len].load_be::<u64>())
+}
+
+/// Returns a ParseError pointing at the hex digit that contains the given bit.
+fn error_at(bit: usize, message: impl Into<String>) -> ParseError {
+    ParseError::new(bit / 4 + 1, message)
 }
 
+/// Packet is a BITS transmission packet, which is either a literal value or an operator on
+/// sub-packets.
 #[derive(Debug)]
-enum Packet {
+pub enum Packet {
     Literal{version: u8, num: u64},
     Operator{version: u8, type_id: u8, packets: Vec<Packet>},
 }
 
 impl Packet {
     /// Parses a packet out of the given hex string.  The packet may contain one or more sub-packets.
-    fn parse(s: &str) -> Self {
+    pub fn parse(s: &str) -> std::result::Result<Self, ParseError> {
         // Translate s from hex digits into bits.
-        let bits: BitVec<Msb0, usize> = s.chars()
-            .flat_map(|c| c.to_digit(16))
-            .fold(BitVec::new(), |mut bits, num| {
-                for i in 0..4 {
-                    bits.push(num & (1 << (3 - i)) != 0);
-                }
-                bits
-            });
+        let mut bits: BitVec<Msb0, usize> = BitVec::new();
+        for (col, c) in s.chars().enumerate() {
+            let num = c.to_digit(16)
+                .ok_or_else(|| ParseError::new(col + 1, format!("expected a hex digit, found '{}'", c)))?;
+
+            for i in 0..4 {
+                bits.push(num & (1 << (3 - i)) != 0);
+            }
+        }
 
         // Parse packets.
-        parse_packet(&bits, 0).0
+        Ok(parse_packet(&bits, 0)?.0)
     }
 
     /// Sums the versions of this packet and it's sub-packets.
-    fn version_sum(&self) -> u64 {
+    pub fn version_sum(&self) -> u64 {
         match self {
             Packet::Literal { version, num: _ } => *version as u64,
             Packet::Operator { version, type_id: _, packets} => {
@@ -123,7 +172,7 @@ impl Packet {
     }
 
     /// Returns the value of this packet and its sub-packets.
-    fn value(&self) -> u64 {
+    pub fn value(&self) -> u64 {
         match self {
             // Type 0: sum
             Packet::Operator { version: _, type_id, packets } if *type_id == 0 =>
@@ -148,30 +197,37 @@ impl Packet {
             // Type 7: equal to
             Packet::Operator { version: _, type_id, packets } if *type_id == 7 =>
                 if packets[0].value() == packets[1].value() { 1 } else { 0 },
-            _ => panic!("Invalid packet type: {:?}", self),
+            _ => unreachable!("Invalid packet type: {:?}", self),
         }
     }
 }
 
 #[test]
 fn version_sum_sample() {
-    assert_eq!(6, Packet::parse("D2FE28").version_sum());
-    assert_eq!(9, Packet::parse("38006F45291200").version_sum());
-    assert_eq!(14, Packet::parse("EE00D40C823060").version_sum());
-    assert_eq!(16, Packet::parse("8A004A801A8002F478").version_sum());
-    assert_eq!(12, Packet::parse("620080001611562C8802118E34").version_sum());
-    assert_eq!(23, Packet::parse("C0015000016115A2E0802F182340").version_sum());
-    assert_eq!(31, Packet::parse("A0016C880162017C3686B18A3D4780").version_sum());
+    assert_eq!(6, Packet::parse("D2FE28").unwrap().version_sum());
+    assert_eq!(9, Packet::parse("38006F45291200").unwrap().version_sum());
+    assert_eq!(14, Packet::parse("EE00D40C823060").unwrap().version_sum());
+    assert_eq!(16, Packet::parse("8A004A801A8002F478").unwrap().version_sum());
+    assert_eq!(12, Packet::parse("620080001611562C8802118E34").unwrap().version_sum());
+    assert_eq!(23, Packet::parse("C0015000016115A2E0802F182340").unwrap().version_sum());
+    assert_eq!(31, Packet::parse("A0016C880162017C3686B18A3D4780").unwrap().version_sum());
 }
 
 #[test]
 fn value_sample() {
-    assert_eq!(3, Packet::parse("C200B40A82").value());
-    assert_eq!(54, Packet::parse("04005AC33890").value());
-    assert_eq!(7, Packet::parse("880086C3E88112").value());
-    assert_eq!(9, Packet::parse("CE00C43D881120").value());
-    assert_eq!(1, Packet::parse("D8005AC2A8F0").value());
-    assert_eq!(0, Packet::parse("F600BC2D8F").value());
-    assert_eq!(0, Packet::parse("9C005AC2F8F0").value());
-    assert_eq!(1, Packet::parse("9C0141080250320F1802104A08").value());
+    assert_eq!(3, Packet::parse("C200B40A82").unwrap().value());
+    assert_eq!(54, Packet::parse("04005AC33890").unwrap().value());
+    assert_eq!(7, Packet::parse("880086C3E88112").unwrap().value());
+    assert_eq!(9, Packet::parse("CE00C43D881120").unwrap().value());
+    assert_eq!(1, Packet::parse("D8005AC2A8F0").unwrap().value());
+    assert_eq!(0, Packet::parse("F600BC2D8F").unwrap().value());
+    assert_eq!(0, Packet::parse("9C005AC2F8F0").unwrap().value());
+    assert_eq!(1, Packet::parse("9C0141080250320F1802104A08").unwrap().value());
+}
+
+#[test]
+fn parse_invalid() {
+    assert_eq!(Err(ParseError::new(3, "expected a hex digit, found 'G'")), Packet::parse("D2G28").map(|p| p.version_sum()));
+    assert_eq!(Err(ParseError::new(5, "packet ended unexpectedly")), Packet::parse("D2FE").map(|p| p.version_sum()));
+    assert_eq!(Err(ParseError::new(1, "operator type 5 can't have 1 sub-packets")), Packet::parse("D6004408").map(|p| p.version_sum()));
 }
@@ -1,34 +1,62 @@
+use std::io::BufRead;
 use std::ops::RangeInclusive;
 use regex::Regex;
 use rayon::prelude::*;
 
-#[allow(dead_code)]
-pub fn solution() {
-    let target = Target::parse("target area: x=144..178, y=-100..-76");
+use crate::error::{parse_num, ParseError, Result};
+use crate::solution::Solution;
 
-    println!("Part 1: {}", target.highest_y());
-    println!("Part 2: {}", target.all_hits());
+/// Day 17: Trick Shot.
+pub struct Day17;
+
+impl Solution for Day17 {
+    type Puzzle = Target;
+    type Part1 = i64;
+    type Part2 = usize;
+
+    fn load<R: BufRead>(reader: R) -> Result<Self::Puzzle> {
+        Target::load(reader)
+    }
+
+    fn part1(target: &Self::Puzzle) -> Result<Self::Part1> {
+        Ok(target.highest_y())
+    }
+
+    fn part2(target: &Self::Puzzle) -> Result<Self::Part2> {
+        Ok(target.all_hits())
+    }
 }
 
-struct Target {
+/// Target is the area the probe needs to land in.
+pub struct Target {
     x_range: RangeInclusive<i64>,
     y_range: RangeInclusive<i64>,
 }
 
 impl Target {
+    /// Loads a target from the first line of the given reader.
+    pub fn load<R: BufRead>(mut reader: R) -> Result<Self> {
+        let mut s = String::new();
+        reader.read_line(&mut s)?;
+
+        Ok(Target::parse(s.trim())?)
+    }
+
     /// Parses a target from the given string like 'target area: x=20..30, y=-10..-5'
-    fn parse(s: &str) -> Self {
+    pub fn parse(s: &str) -> std::result::Result<Self, ParseError> {
         let re = Regex::new(r"^target area: x=(-?\d+)\.\.(-?\d+), y=(-?\d+)\.\.(-?\d+)").unwrap();
-        let captures = re.captures(s).unwrap();
-
-        Target {
-            x_range: captures[1].parse().unwrap() ..= captures[2].parse().unwrap(),
-            y_range: captures[3].parse().unwrap() ..= captures[4].parse().unwrap(),
-        }
+        let captures = re.captures(s)
+            .ok_or_else(|| ParseError::new(1, format!("expected a target like 'target area: x=20..30, y=-10..-5', found '{}'", s)))?;
+        let num = |i: usize| parse_num(s, captures.get(i).unwrap().as_str());
+
+        Ok(Target {
+            x_range: num(1)? ..= num(2)?,
+            y_range: num(3)? ..= num(4)?,
+        })
     }
 
     /// Returns the highest y position that the probe can reach and still hit the target area.
-    fn highest_y(&self) -> i64 {
+    pub fn highest_y(&self) -> i64 {
         // brute force.
         (0..1000).into_par_iter().flat_map(|x| {
             (-1000..1000).into_par_iter()
@@ -38,7 +66,7 @@ impl Target {
 
     /// Returns the number of initial velocity values that cause the probe to eventually be within
     /// the target area.
-    fn all_hits(&self) -> usize {
+    pub fn all_hits(&self) -> usize {
         // also brute force.
         (0..1000).into_par_iter().flat_map(|x| {
             (-1000..1000).into_par_iter()
@@ -137,12 +165,16 @@ impl Probe {
 
 #[test]
 fn highest_y_sample() {
-    let target = Target::parse("target area: x=20..30, y=-10..-5");
+    let target = Target::parse("target area: x=20..30, y=-10..-5").unwrap();
     assert_eq!(45, target.highest_y());
 }
 
 #[test]
 fn all_hits_sample() {
-    let target = Target::parse("target area: x=20..30, y=-10..-5");
+    let target = Target::parse("target area: x=20..30, y=-10..-5").unwrap();
     assert_eq!(112, target.all_hits());
-}
\ No newline at end of file
+}
+#[test]
+fn parse_invalid() {
+    assert_eq!(Some(ParseError::new(1, "expected a target like 'target area: x=20..30, y=-10..-5', found 'x=1..2'")), Target::parse("x=1..2").err());
+}
@@ -1,49 +1,81 @@
 use std::fmt::{Debug, Formatter};
-use std::fs::File;
-use std::io::{BufRead, BufReader};
+use std::io::BufRead;
 use std::iter;
 use std::ops::Add;
 use std::str::FromStr;
-use std::string::ParseError;
 
-#[allow(dead_code)]
-pub fn solution() {
-    let numbers = load("input/day18.txt");
+use crate::error::{parse_num, Error, ParseError, Result};
+use crate::solution::Solution;
 
-    println!("Part 1: {}", sum(&numbers).magnitude());
-    println!("Part 2: {}", largest_magnitude(&numbers));
+/// Day 18: Snailfish.
+pub struct Day18;
+
+impl Solution for Day18 {
+    type Puzzle = Vec<Number>;
+    type Part1 = i32;
+    type Part2 = i32;
+
+    fn load<R: BufRead>(reader: R) -> Result<Self::Puzzle> {
+        load(reader)
+    }
+
+    fn part1(numbers: &Self::Puzzle) -> Result<Self::Part1> {
+        Ok(sum(numbers)?.magnitude())
+    }
+
+    fn part2(numbers: &Self::Puzzle) -> Result<Self::Part2> {
+        largest_magnitude(numbers)
+    }
 }
 
-/// Loads pairs from the given file, one per line.
-fn load(filename: &str) -> Vec<Number> {
-    let f = File::open(filename).unwrap();
-    let f = BufReader::new(f);
+/// Loads pairs from the given input, one per line.
+/// Every number must already be reduced.
+pub fn load<R: BufRead>(reader: R) -> Result<Vec<Number>> {
+    let mut numbers = Vec::new();
+
+    for (i, line) in reader.lines().enumerate() {
+        let line = line?;
+        let line = line.trim();
+        if line.is_empty() {
+            continue;
+        }
+
+        let number: Number = line.parse().map_err(|e: ParseError| e.on_line(i + 1))?;
+
+        // Addition assumes both sides are already reduced.
+        if !number.is_reduced() {
+            return Err(ParseError::new(1, format!("expected a reduced number, found '{}'", line))
+                .on_line(i + 1).into());
+        }
 
-    f.lines()
-        .flat_map(|line| line.unwrap().parse::<Number>())
-        .collect()
+        numbers.push(number);
+    }
+
+    Ok(numbers)
 }
 
 /// Returns the sum of all of the numbers.
-fn sum(numbers: &Vec<Number>) -> Number {
+pub fn sum(numbers: &[Number]) -> Result<Number> {
     let mut it = numbers.iter();
-    let start = it.next().unwrap();
+    let start = it.next()
+        .ok_or_else(|| Error::no_solution("There aren't any numbers to add."))?;
 
-    it.fold(start.clone(), |acc, number| acc + number.clone())
+    Ok(it.fold(start.clone(), |acc, number| acc + number.clone()))
 }
 
 /// Returns the largest magnitude of any sum of two different numbers.
-fn largest_magnitude(nums: &Vec<Number>) -> i32 {
+pub fn largest_magnitude(nums: &[Number]) -> Result<i32> {
     (0..nums.len())
         .flat_map(move |i| (0..nums.len()).map(move |j| (i, j)))
         .filter(|(i, j)| i != j)
         .map(|(i, j)| (nums[i].clone() + nums[j].clone()).magnitude())
         .max()
-        .unwrap()
+        .ok_or_else(|| Error::no_solution("Need at least two numbers to add."))
 }
 
+/// Number is a snailfish number, which is a pair of regular numbers or other pairs.
 #[derive(Debug, Eq, PartialEq, Clone)]
-struct Number {
+pub struct Number {
     value: Vec<Element>
 }
 
@@ -67,38 +99,61 @@ impl Debug for Element {
 impl FromStr for Number {
     type Err = ParseError;
 
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
         let mut elements = Vec::new();
-        let mut last_num = None;
-
-        for c in s.chars() {
-            if last_num.is_some() && (c == '[' || c == ']' || c == ',') {
-                elements.push(Element::Number(last_num.unwrap()));
-                last_num = None;
-            }
+        let end = parse_element(s, 0, &mut elements)?;
 
-            match c {
-                '[' => elements.push(Element::Open),
-                ']' => elements.push(Element::Close),
-                value if c.is_numeric() => {
-                    last_num = Some(last_num.unwrap_or(0) * 10 + value.to_digit(10).unwrap() as i32)
-                },
-                _ => {}
-            }
+        if let Some(c) = s[end..].chars().next() {
+            return Err(ParseError::new(end + 1, format!("unexpected '{}' after the number", c)));
         }
 
         Ok(Number { value: elements })
     }
 }
 
+/// Parses a regular number or a pair that starts at index i of the given string into elements,
+/// returning the index after it.
+fn parse_element(s: &str, i: usize, elements: &mut Vec<Element>) -> std::result::Result<usize, ParseError> {
+    match s[i..].chars().next() {
+        Some('[') => {
+            // Pair looks like '[left,right]'.
+            elements.push(Element::Open);
+            let i = parse_element(s, i + 1, elements)?;
+            let i = expect(s, i, ',')?;
+            let i = parse_element(s, i, elements)?;
+            let i = expect(s, i, ']')?;
+            elements.push(Element::Close);
+
+            Ok(i)
+        }
+        Some(c) if c.is_ascii_digit() => {
+            let len = s[i..].find(|c: char| !c.is_ascii_digit()).unwrap_or(s.len() - i);
+            elements.push(Element::Number(parse_num(s, &s[i..i + len])?));
+
+            Ok(i + len)
+        }
+        Some(c) => Err(ParseError::new(i + 1, format!("expected '[' or a digit, found '{}'", c))),
+        None => Err(ParseError::new(i + 1, "number ended unexpectedly")),
+    }
+}
+
+/// Checks that the given character is at index i of the string, returning the index after it.
+fn expect(s: &str, i: usize, expected: char) -> std::result::Result<usize, ParseError> {
+    match s[i..].chars().next() {
+        Some(c) if c == expected => Ok(i + 1),
+        Some(c) => Err(ParseError::new(i + 1, format!("expected '{}', found '{}'", expected, c))),
+        None => Err(ParseError::new(i + 1, format!("expected '{}', but the number ended", expected))),
+    }
+}
+
 impl Add for Number {
     type Output = Number;
 
     fn add(self, rhs: Self) -> Self::Output {
         // Adding two Numbers results in a pair of numbers.
         let result_value = iter::once(Element::Open)
-            .chain(self.value.into_iter())
-            .chain(rhs.value.into_iter())
+            .chain(self.value)
+            .chain(rhs.value)
             .chain(iter::once(Element::Close))
             .collect();
 
@@ -112,6 +167,12 @@ impl Add for Number {
 }
 
 impl Number {
+    /// Returns whether this number is already reduced, which means it can't explode or split.
+    pub fn is_reduced(&self) -> bool {
+        // Reducing happens in place, so try reducing a copy.
+        !self.clone().reduce()
+    }
+
     /// Reduces this pair, modifying it in place.  Returns true if the pair was reduced, or
     /// false if no more reductions can be made.
     fn reduce(&mut self) -> bool {
@@ -153,7 +214,7 @@ impl Number {
 
                             // Add the right number into the next-closest right number.
                             let maybe_right_add_idx = ((i + 3)..self.value.len())
-                                .find(|r| if let Element::Number(_) = self.value[*r] { true } else { false });
+                                .find(|r| matches!(self.value[*r], Element::Number(_)));
                             if let Some(right_add_idx) = maybe_right_add_idx {
                                 if let Element::Number(right_add_value) = self.value[right_add_idx] {
                                     self.value[right_add_idx] = Element::Number(right_add_value + right);
@@ -196,7 +257,7 @@ impl Number {
 
     /// Returns the magnitude of this pair.
     /// The magnitude of a pair is `3 * left + 2 * right`, recursive.
-    fn magnitude(&self) -> i32 {
+    pub fn magnitude(&self) -> i32 {
         let mut stack = Vec::new();
 
         for e in &self.value {
@@ -250,8 +311,8 @@ fn sample_explode() {
     start.reduce();
     assert_eq!(expected, start);
 
-    let mut start: Number = "[[3,[2,[[4,1],[7,3]]],0]".parse().unwrap();
-    let expected: Number = "[[3,[6,[0,[8,3]]],0]".parse().unwrap();
+    let mut start: Number = "[[3,[2,[[4,1],[7,3]]]],0]".parse().unwrap();
+    let expected: Number = "[[3,[6,[0,[8,3]]]],0]".parse().unwrap();
     start.reduce();
     assert_eq!(expected, start);
 }
@@ -277,15 +338,15 @@ fn sample_split() {
 #[test]
 fn sample_sums() {
     let expected: Number = "[[[[1,1],[2,2]],[3,3]],[4,4]]".parse().unwrap();
-    assert_eq!(expected, sum(&load("input/day18_sample.txt")));
+    assert_eq!(expected, sum(&load(std::fs::read_to_string("input/day18_sample.txt").unwrap().as_bytes()).unwrap()).unwrap());
     let expected: Number = "[[[[3,0],[5,3]],[4,4]],[5,5]]".parse().unwrap();
-    assert_eq!(expected, sum(&load("input/day18_sample2.txt")));
+    assert_eq!(expected, sum(&load(std::fs::read_to_string("input/day18_sample2.txt").unwrap().as_bytes()).unwrap()).unwrap());
     let expected: Number = "[[[[5,0],[7,4]],[5,5]],[6,6]]".parse().unwrap();
-    assert_eq!(expected, sum(&load("input/day18_sample3.txt")));
+    assert_eq!(expected, sum(&load(std::fs::read_to_string("input/day18_sample3.txt").unwrap().as_bytes()).unwrap()).unwrap());
     let expected: Number = "[[[[8,7],[7,7]],[[8,6],[7,7]]],[[[0,7],[6,6]],[8,7]]]".parse().unwrap();
-    assert_eq!(expected, sum(&load("input/day18_sample4.txt")));
+    assert_eq!(expected, sum(&load(std::fs::read_to_string("input/day18_sample4.txt").unwrap().as_bytes()).unwrap()).unwrap());
     let expected: Number = "[[[[6,6],[7,6]],[[7,7],[7,0]]],[[[7,7],[7,7]],[[7,8],[9,9]]]]".parse().unwrap();
-    assert_eq!(expected, sum(&load("input/day18_sample5.txt")));
+    assert_eq!(expected, sum(&load(std::fs::read_to_string("input/day18_sample5.txt").unwrap().as_bytes()).unwrap()).unwrap());
 }
 
 #[test]
@@ -300,5 +361,18 @@ fn sample_magnitudes() {
 
 #[test]
 fn sample_largest_magnitude() {
-    assert_eq!(3993, largest_magnitude(&load("input/day18_sample5.txt")));
+    assert_eq!(3993, largest_magnitude(&load(std::fs::read_to_string("input/day18_sample5.txt").unwrap().as_bytes()).unwrap()).unwrap());
+}
+
+#[test]
+fn parse_invalid() {
+    assert_eq!(Err(ParseError::new(4, "expected ',', found ']'")), "[12]".parse::<Number>());
+    assert_eq!(Err(ParseError::new(4, "expected '[' or a digit, found 'x'")), "[1,x]".parse::<Number>());
+    assert_eq!(Err(ParseError::new(6, "unexpected ']' after the number")), "[1,2]]".parse::<Number>());
+    assert_eq!(Err(ParseError::new(5, "expected ']', but the number ended")), "[1,2".parse::<Number>());
+
+    match load("[1,2]\n[10,2]\n".as_bytes()) {
+        Err(Error::Parse(e)) => assert_eq!(ParseError { line: 2, column: 1, message: "expected a reduced number, found '[10,2]'".to_string() }, e),
+        other => panic!("Expected a parse error, got {:?}", other),
+    }
 }
@@ -0,0 +1,273 @@
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::io::BufRead;
+use std::ops::{Add, Sub};
+use std::str::FromStr;
+
+use crate::error::{parse_num, Error, ParseError, Result};
+use crate::solution::Solution;
+
+/// Number of beacons that two scanners need to have in common to be lined up.
+const OVERLAP: usize = 12;
+
+/// Number of ways to rotate a scanner - it can face 6 directions, and have 4 directions as up.
+const ROTATIONS: usize = 24;
+
+/// Day 19: Beacon Scanner.
+pub struct Day19;
+
+impl Solution for Day19 {
+    type Puzzle = BeaconMap;
+    type Part1 = usize;
+    type Part2 = i32;
+
+    fn load<R: BufRead>(reader: R) -> Result<Self::Puzzle> {
+        // Lining up the scanners is the slow part, so it's done once for both parts.
+        assemble(&load(reader)?)
+    }
+
+    fn part1(map: &Self::Puzzle) -> Result<Self::Part1> {
+        Ok(map.beacons.len())
+    }
+
+    fn part2(map: &Self::Puzzle) -> Result<Self::Part2> {
+        Ok(map.largest_distance())
+    }
+}
+
+/// Point is a position in 3D space.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub struct Point {
+    pub x: i32,
+    pub y: i32,
+    pub z: i32,
+}
+
+impl Point {
+    pub fn new(x: i32, y: i32, z: i32) -> Self {
+        Point { x, y, z }
+    }
+
+    /// Returns the manhattan distance between this point and the other point.
+    pub fn distance(&self, other: &Point) -> i32 {
+        (self.x - other.x).abs() + (self.y - other.y).abs() + (self.z - other.z).abs()
+    }
+
+    /// Returns this point rotated around the origin by one of the 24 rotations, numbered
+    /// `0..24`.  Rotation 0 leaves the point where it is.
+    pub fn rotate(&self, rotation: usize) -> Point {
+        let Point { x, y, z } = *self;
+
+        // Turn to face one of 6 directions...
+        let (x, y, z) = match rotation / 4 {
+            0 => (x, y, z),
+            1 => (-x, -y, z),
+            2 => (y, -x, z),
+            3 => (-y, x, z),
+            4 => (z, y, -x),
+            _ => (-z, y, x),
+        };
+
+        // ... then spin around the direction being faced.
+        match rotation % 4 {
+            0 => Point::new(x, y, z),
+            1 => Point::new(x, -z, y),
+            2 => Point::new(x, -y, -z),
+            _ => Point::new(x, z, -y),
+        }
+    }
+}
+
+impl Add for Point {
+    type Output = Point;
+
+    fn add(self, other: Point) -> Point {
+        Point::new(self.x + other.x, self.y + other.y, self.z + other.z)
+    }
+}
+
+impl Sub for Point {
+    type Output = Point;
+
+    fn sub(self, other: Point) -> Point {
+        Point::new(self.x - other.x, self.y - other.y, self.z - other.z)
+    }
+}
+
+impl FromStr for Point {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        // Point looks like '-618,-824,-621'
+        let nums = s.split(',')
+            .map(|n| parse_num(s, n.trim()))
+            .collect::<std::result::Result<Vec<i32>, ParseError>>()?;
+
+        match nums[..] {
+            [x, y, z] => Ok(Point::new(x, y, z)),
+            _ => Err(ParseError::new(1, format!("expected a point like 'x,y,z', found '{}'", s))),
+        }
+    }
+}
+
+/// Scanner is a scanner's report: the beacons it can see, relative to the scanner and facing
+/// whichever way the scanner faces.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct Scanner {
+    pub beacons: Vec<Point>,
+}
+
+/// Loads scanner reports from the given input.  Each report starts with a line like
+/// '--- scanner 0 ---', followed by a line with the position of each beacon.
+pub fn load<R: BufRead>(reader: R) -> Result<Vec<Scanner>> {
+    let mut scanners: Vec<Scanner> = Vec::new();
+
+    for (i, line) in reader.lines().enumerate() {
+        let line = line?;
+        let line = line.trim_end();
+        let error = |e: ParseError| e.on_line(i + 1);
+
+        if line.is_empty() {
+            continue;
+        }
+
+        if line.starts_with("---") {
+            scanners.push(Scanner { beacons: Vec::new() });
+            continue;
+        }
+
+        let beacon = line.parse().map_err(error)?;
+        match scanners.last_mut() {
+            Some(scanner) => scanner.beacons.push(beacon),
+            None => return Err(error(ParseError::new(1, format!("expected a header like '--- scanner 0 ---', found '{}'", line))).into()),
+        }
+    }
+
+    if scanners.is_empty() {
+        return Err(ParseError::new(1, "expected at least one scanner").into());
+    }
+
+    Ok(scanners)
+}
+
+/// BeaconMap is the position of every beacon and scanner, relative to the first scanner.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct BeaconMap {
+    pub beacons: HashSet<Point>,
+    pub scanners: Vec<Point>,
+}
+
+impl BeaconMap {
+    /// Returns the largest manhattan distance between any two scanners.
+    pub fn largest_distance(&self) -> i32 {
+        self.scanners.iter()
+            .flat_map(|a| self.scanners.iter().map(move |b| a.distance(b)))
+            .max()
+            .unwrap_or(0)
+    }
+}
+
+/// Lines up the scanners into a map of every beacon, relative to the first scanner.  Scanners
+/// line up when they have at least 12 beacons in common.  Returns an error if there aren't any
+/// scanners, or if a scanner can't be lined up with the others.
+pub fn assemble(scanners: &[Scanner]) -> Result<BeaconMap> {
+    let first = scanners.first().ok_or_else(|| Error::no_solution("There aren't any scanners to line up."))?;
+
+    // Beacons and positions of scanners that have been lined up, relative to the first scanner.
+    let mut aligned: Vec<Option<(Vec<Point>, Point)>> = vec![None; scanners.len()];
+    aligned[0] = Some((first.beacons.clone(), Point::new(0, 0, 0)));
+
+    // Each scanner only needs to be compared against the others once, right after it's lined up.
+    let mut to_compare = VecDeque::from([0]);
+    while let Some(known) = to_compare.pop_front() {
+        for other in 0..scanners.len() {
+            if aligned[other].is_some() {
+                continue;
+            }
+
+            let known_beacons = &aligned[known].as_ref().unwrap().0;
+            if let Some((rotation, position)) = align(known_beacons, &scanners[other]) {
+                let beacons = scanners[other].beacons.iter()
+                    .map(|beacon| beacon.rotate(rotation) + position)
+                    .collect();
+
+                aligned[other] = Some((beacons, position));
+                to_compare.push_back(other);
+            }
+        }
+    }
+
+    let mut map = BeaconMap { beacons: HashSet::new(), scanners: Vec::new() };
+    for (i, scanner) in aligned.into_iter().enumerate() {
+        let (beacons, position) = scanner
+            .ok_or_else(|| Error::no_solution(format!("Scanner {} doesn't overlap with the others.", i)))?;
+
+        map.beacons.extend(beacons);
+        map.scanners.push(position);
+    }
+
+    Ok(map)
+}
+
+/// Tries every rotation of the scanner, looking for one where at least 12 of its beacons line
+/// up with the known beacons.  Returns the rotation and the position of the scanner relative to
+/// the known beacons, or None if the scanner doesn't overlap.
+fn align(known: &[Point], scanner: &Scanner) -> Option<(usize, Point)> {
+    for rotation in 0..ROTATIONS {
+        let rotated = scanner.beacons.iter().map(|beacon| beacon.rotate(rotation)).collect::<Vec<Point>>();
+
+        // If the scanner is at some position, then many known beacons will be that far from
+        // the scanner's beacons.
+        let mut positions: HashMap<Point, usize> = HashMap::new();
+        for known_beacon in known {
+            for &beacon in &rotated {
+                let count = positions.entry(*known_beacon - beacon).or_insert(0);
+                *count += 1;
+
+                if *count >= OVERLAP {
+                    return Some((rotation, *known_beacon - beacon));
+                }
+            }
+        }
+    }
+
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use super::*;
+
+    #[test]
+    fn test_sample() {
+        let scanners = load(fs::read_to_string("input/day19_sample.txt").unwrap().as_bytes()).unwrap();
+        let map = assemble(&scanners).unwrap();
+
+        assert_eq!(78, map.beacons.len());
+        assert_eq!(5767, map.largest_distance());
+        assert!(matches!(assemble(&[]), Err(Error::NoSolution(_))));
+    }
+
+    #[test]
+    fn rotations() {
+        let point = Point::new(1, 2, 3);
+        let rotated = (0..ROTATIONS).map(|rotation| point.rotate(rotation)).collect::<HashSet<Point>>();
+
+        assert_eq!(ROTATIONS, rotated.len());
+        assert_eq!(point, point.rotate(0));
+    }
+
+    #[test]
+    fn load_invalid() {
+        match load("--- scanner 0 ---\n1,2,3\n4,x,6\n".as_bytes()) {
+            Err(Error::Parse(e)) => assert_eq!(ParseError::new(3, "expected a number, found 'x'").on_line(3), e),
+            other => panic!("Expected a parse error, got {:?}", other),
+        }
+
+        match load("1,2,3\n".as_bytes()) {
+            Err(Error::Parse(e)) => assert_eq!(ParseError::new(1, "expected a header like '--- scanner 0 ---', found '1,2,3'").on_line(1), e),
+            other => panic!("Expected a parse error, got {:?}", other),
+        }
+    }
+}
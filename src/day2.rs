@@ -1,63 +1,253 @@
-use std::fs::File;
-use std::io::{BufRead, BufReader};
+use std::io::BufRead;
 use std::ops::Add;
 use std::str::FromStr;
 
-#[allow(dead_code)]
-pub fn solution() {
-    let directions = load("input/day2.txt");
+use crate::error::{parse_num, Error, ParseError, Result};
+use crate::solution::Solution;
 
-    println!("Part 1: {}", distance(&directions));
-    println!("Part 2: {}", aim_distance(&directions));
+/// Day 2: Dive!.
+pub struct Day2;
+
+impl Solution for Day2 {
+    type Puzzle = Vec<Direction>;
+    type Part1 = i32;
+    type Part2 = i32;
+
+    fn load<R: BufRead>(reader: R) -> Result<Self::Puzzle> {
+        load(reader)
+    }
+
+    fn part1(directions: &Self::Puzzle) -> Result<Self::Part1> {
+        Ok(distance(directions))
+    }
+
+    fn part2(directions: &Self::Puzzle) -> Result<Self::Part2> {
+        Ok(aim_distance(directions))
+    }
 }
 
-fn load(filename: &str) -> Vec<Direction> {
-    let f = File::open(filename).unwrap();
-    let f = BufReader::new(f);
+/// Directions that the submarine understands.
+const VERBS: [&str; 4] = ["forward", "back", "down", "up"];
 
-    f.lines()
-        .flat_map(|line| line.unwrap().parse::<Direction>())
-        .collect()
+/// Most directions that a command script can expand to once its `repeat` blocks are followed.
+pub const MAX_DIRECTIONS: usize = 1_000_000;
+
+/// Mode is how a command script treats commands that the submarine doesn't understand.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum Mode {
+    /// Unknown commands are errors.
+    Strict,
+    /// Unknown commands are skipped.
+    Lenient,
 }
 
-/// Follows the given directions and returns the horizontal position multiplied by the final depth.
-fn distance(directions: &Vec<Direction>) -> i32 {
-    let end_position= directions.iter().fold(Position::default(), |pos, dir| pos + dir);
+/// Loads directions from the given input, one per line.  Unknown commands are errors.
+pub fn load<R: BufRead>(reader: R) -> Result<Vec<Direction>> {
+    load_script(reader, Mode::Strict)
+}
+
+/// Loads a command script, which has one direction per line, like
+///
+/// ```text
+/// # Dive, then come back up.
+/// forward 5
+/// repeat 3 {
+///   down 2   # Each repeat goes deeper.
+/// }
+/// back 1
+/// ```
+///
+/// Anything after a `#` is a comment, and the directions in a `repeat N { ... }` block are
+/// followed N times.  Blocks can be nested, but a script can expand to at most `MAX_DIRECTIONS`
+/// directions.  Errors have the line and column where they happened.
+pub fn load_script<R: BufRead>(reader: R, mode: Mode) -> Result<Vec<Direction>> {
+    // Directions outside of each open block, with where the block started and its number of repeats.
+    let mut blocks: Vec<(usize, &str, usize, Vec<Direction>)> = Vec::new();
+    let mut directions = Vec::new();
+
+    // Lines need to outlive the blocks that start on them, for errors about unclosed blocks.
+    let lines = reader.lines().collect::<std::io::Result<Vec<String>>>()?;
+
+    for (i, line) in lines.iter().enumerate() {
+        let error = |e: ParseError| e.on_line(i + 1);
+        let command = line.split('#').next().unwrap().trim_end();
+        let words = command.split_whitespace().collect::<Vec<&str>>();
 
-    end_position.depth * end_position.distance
+        match words[..] {
+            [] => {}
+            ["repeat", count, "{"] => {
+                let count = parse_num(command, count).map_err(error)?;
+                blocks.push((i + 1, words[0], count, directions));
+                directions = Vec::new();
+            }
+            ["repeat", ..] =>
+                return Err(error(ParseError::at(command, words[0], format!("expected a block like 'repeat 3 {{', found '{}'", command.trim()))).into()),
+            ["}"] => {
+                let (line, repeat, count, mut outer) = blocks.pop()
+                    .ok_or_else(|| error(ParseError::at(command, words[0], "found '}' without a 'repeat' block")))?;
+
+                let expanded = directions.len().checked_mul(count).and_then(|len| len.checked_add(outer.len()));
+                if expanded.is_none_or(|len| len > MAX_DIRECTIONS) {
+                    let message = format!("expected at most {} directions after repeating", MAX_DIRECTIONS);
+                    return Err(ParseError::at(&lines[line - 1], repeat, message).on_line(line).into());
+                }
+
+                // Empty blocks can repeat any number of times without adding anything.
+                outer.extend(directions.iter().cloned().cycle().take(directions.len() * count));
+                directions = outer;
+            }
+            [verb, ..] if mode == Mode::Lenient && !VERBS.contains(&verb) => {}
+            _ => directions.push(command.parse().map_err(error)?),
+        }
+    }
+
+    match blocks.pop() {
+        Some((line, repeat, _, _)) =>
+            Err(ParseError::at(&lines[line - 1], repeat, "missing a '}' to close the 'repeat' block").on_line(line).into()),
+        None => Ok(directions),
+    }
+}
+
+/// Follows the given directions and returns the horizontal position multiplied by the final depth.
+pub fn distance(directions: &[Direction]) -> i32 {
+    follow::<Position>(directions).product()
 }
 
 /// Follows the given directions and returns horizontal position * depth.  Up and down directions
 /// aim the submarine instead of moving it up and down.
-fn aim_distance(directions: &Vec<Direction>) -> i32 {
-    let end_position= directions.iter().fold(AimPosition::default(), |pos, dir| pos + dir);
+pub fn aim_distance(directions: &[Direction]) -> i32 {
+    follow::<AimPosition>(directions).product()
+}
+
+/// Model is a way of interpreting directions to move the submarine.  Models start at the
+/// default position.
+pub trait Model: Default {
+    /// Returns where the submarine ends up after following the direction from this position.
+    fn follow(self, dir: &Direction) -> Self;
 
-    end_position.depth * end_position.distance
+    /// Returns where the submarine is.
+    fn location(&self) -> Location;
 }
 
-#[derive(Debug, Eq, PartialEq)]
-struct ParseErr {}
+/// Location is where the submarine is: how deep it is, and how far forward it's gone.
+#[derive(Debug, Default, Copy, Clone, Eq, PartialEq)]
+pub struct Location {
+    pub depth: i32,
+    pub distance: i32,
+}
+
+impl Location {
+    pub fn new(depth: i32, distance: i32) -> Self {
+        Location { depth, distance }
+    }
+
+    /// Returns the depth multiplied by the forward distance.
+    pub fn product(&self) -> i32 {
+        self.depth * self.distance
+    }
+}
+
+/// Follows the given directions with the model, and returns where the submarine ends up.
+pub fn follow<M: Model>(directions: &[Direction]) -> Location {
+    directions.iter().fold(M::default(), |pos, dir| pos.follow(dir)).location()
+}
+
+/// Follows the given directions with the model, and returns every location the submarine
+/// visits, starting with where it starts and ending with where it ends up.
+pub fn trajectory<M: Model>(directions: &[Direction]) -> Vec<Location> {
+    let mut pos = M::default();
+    let mut locations = vec![pos.location()];
+
+    for dir in directions {
+        pos = pos.follow(dir);
+        locations.push(pos.location());
+    }
+
+    locations
+}
+
+/// TrajectoryFn follows directions with a particular model, like `trajectory::<Position>`.
+type TrajectoryFn = fn(&[Direction]) -> Vec<Location>;
+
+/// Models is a registry of models by name, so models can be picked and compared at runtime.
+/// The default registry has Position as 'position' and AimPosition as 'aim'.
+pub struct Models {
+    models: Vec<(String, TrajectoryFn)>,
+}
+
+impl Models {
+    /// Returns a registry without any models.
+    pub fn empty() -> Self {
+        Models { models: Vec::new() }
+    }
+
+    /// Registers the model under the given name, replacing any model that already has it.
+    pub fn register<M: Model>(&mut self, name: &str) -> &mut Self {
+        let model = trajectory::<M> as TrajectoryFn;
+
+        match self.models.iter_mut().find(|(existing, _)| existing == name) {
+            Some(registered) => registered.1 = model,
+            None => self.models.push((name.to_string(), model)),
+        }
+
+        self
+    }
+
+    /// Returns the names of the registered models, in the order they were registered.
+    pub fn names(&self) -> impl Iterator<Item = &str> {
+        self.models.iter().map(|(name, _)| name.as_str())
+    }
+
+    /// Follows the given directions with the named model, and returns every location the
+    /// submarine visits.  Returns an error if there isn't a model with that name.
+    pub fn trajectory(&self, name: &str, directions: &[Direction]) -> Result<Vec<Location>> {
+        let (_, model) = self.models.iter()
+            .find(|(registered, _)| registered == name)
+            .ok_or_else(|| Error::no_solution(format!("Unknown model '{}'.", name)))?;
+
+        Ok(model(directions))
+    }
+}
+
+impl Default for Models {
+    fn default() -> Self {
+        let mut models = Models::empty();
+        models.register::<Position>("position").register::<AimPosition>("aim");
+        models
+    }
+}
 
 /// Direction describes where and how far the submarine should move.
-#[derive(Debug, Eq, PartialEq)]
-enum Direction {
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub enum Direction {
     Forward(i32),
+    Back(i32),
     Down(i32),
     Up(i32),
 }
 
 impl FromStr for Direction {
-    type Err = ParseErr;
+    type Err = ParseError;
 
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
         // String looks like 'forward 5'
         let mut parts = s.split_whitespace();
+        let (direction, amount) = (parts.next(), parts.next());
+
+        if let (Some("forward" | "back" | "down" | "up"), Some(_), Some(extra)) = (direction, amount, parts.next()) {
+            return Err(ParseError::at(s, extra, format!("unexpected '{}' after the amount", extra)));
+        }
 
-        match (parts.next(), parts.next()) {
-            (Some("forward"), Some(amount)) => Ok(Direction::Forward(amount.parse().unwrap())),
-            (Some("down"), Some(amount)) => Ok(Direction::Down(amount.parse().unwrap())),
-            (Some("up"), Some(amount)) => Ok(Direction::Up(amount.parse().unwrap())),
-            _ => Err(ParseErr {}),
+        match (direction, amount) {
+            (Some("forward"), Some(amount)) => Ok(Direction::Forward(parse_num(s, amount)?)),
+            (Some("back"), Some(amount)) => Ok(Direction::Back(parse_num(s, amount)?)),
+            (Some("down"), Some(amount)) => Ok(Direction::Down(parse_num(s, amount)?)),
+            (Some("up"), Some(amount)) => Ok(Direction::Up(parse_num(s, amount)?)),
+            (Some(direction @ ("forward" | "back" | "down" | "up")), None) =>
+                Err(ParseError::new(s.len() + 1, format!("missing an amount after '{}'", direction))),
+            (Some(direction), _) =>
+                Err(ParseError::at(s, direction, format!("unknown direction '{}'", direction))),
+            (None, _) => Err(ParseError::new(1, "missing a direction")),
         }
     }
 }
@@ -65,7 +255,7 @@ impl FromStr for Direction {
 /// Position captures the submarine's depth and forward distance.  Up and Down directions
 /// move the sub up and down.
 #[derive(Debug, Eq, PartialEq)]
-struct Position {
+pub struct Position {
     depth: i32,
     distance: i32,
 }
@@ -91,6 +281,10 @@ impl Add<&Direction> for Position {
                 depth: self.depth,
                 distance: self.distance + amount,
             },
+            Direction::Back(amount) => Position {
+                depth: self.depth,
+                distance: self.distance - amount,
+            },
             Direction::Down(amount) => Position {
                 depth: self.depth + amount,
                 distance: self.distance,
@@ -103,10 +297,20 @@ impl Add<&Direction> for Position {
     }
 }
 
+impl Model for Position {
+    fn follow(self, dir: &Direction) -> Self {
+        self + dir
+    }
+
+    fn location(&self) -> Location {
+        Location::new(self.depth, self.distance)
+    }
+}
+
 /// AimPosition captures the sub's depth, forward distance, and aim angle.  Up and Down directions
 /// adjust the sub's aim, and Forward adjusts it's forward and horizontal positions based on the aim.
 #[derive(Debug, Eq, PartialEq)]
-struct AimPosition {
+pub struct AimPosition {
     depth: i32,
     distance: i32,
     aim: i32,
@@ -134,6 +338,11 @@ impl Add<&Direction> for AimPosition {
                 distance: self.distance + amount,
                 aim: self.aim,
             },
+            Direction::Back(amount) => AimPosition {
+                depth: self.depth - self.aim * amount,
+                distance: self.distance - amount,
+                aim: self.aim,
+            },
             Direction::Down(amount) => AimPosition {
                 depth: self.depth,
                 distance: self.distance,
@@ -148,6 +357,16 @@ impl Add<&Direction> for AimPosition {
     }
 }
 
+impl Model for AimPosition {
+    fn follow(self, dir: &Direction) -> Self {
+        self + dir
+    }
+
+    fn location(&self) -> Location {
+        Location::new(self.depth, self.distance)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -157,7 +376,12 @@ mod tests {
         assert_eq!(Ok(Direction::Forward(3)), "forward 3".parse());
         assert_eq!(Ok(Direction::Down(5)), "down 5".parse());
         assert_eq!(Ok(Direction::Up(10)), "up 10".parse());
-        assert_eq!(Err(ParseErr {}), "not a direction".parse::<Direction>());
+        assert_eq!(Ok(Direction::Back(2)), "back 2".parse());
+        assert_eq!(Err(ParseError::new(1, "unknown direction 'not'")), "not a direction".parse::<Direction>());
+        assert_eq!(Err(ParseError::new(9, "expected a number, found 'x'")), "forward x".parse::<Direction>());
+        assert_eq!(Err(ParseError::new(5, "missing an amount after 'up'")), "  up".parse::<Direction>());
+        assert_eq!(Err(ParseError::new(11, "unexpected '6' after the amount")), "forward 5 6".parse::<Direction>());
+        assert_eq!(Err(ParseError::new(6, "unexpected 'junk' after the amount")), "up 3 junk".parse::<Direction>());
     }
 
     #[test]
@@ -187,6 +411,7 @@ mod tests {
         assert_eq!(AimPosition::new(15, 3, 5), AimPosition::new(0, 0, 5) + &Direction::Forward(3));
         assert_eq!(AimPosition::new(0, 0, 5), AimPosition::default() + &Direction::Down(5));
         assert_eq!(AimPosition::new(0, 0, -10), AimPosition::default() + &Direction::Up(10));
+        assert_eq!(AimPosition::new(0, 0, 5), AimPosition::new(15, 3, 5) + &Direction::Back(3));
     }
 
     #[test]
@@ -202,4 +427,89 @@ mod tests {
 
         assert_eq!(900, aim_distance(&directions))
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn trajectories() {
+        let directions = vec![Direction::Forward(5), Direction::Down(5), Direction::Forward(8)];
+
+        assert_eq!(vec![Location::new(0, 0), Location::new(0, 5), Location::new(5, 5), Location::new(5, 13)],
+                   trajectory::<Position>(&directions));
+        assert_eq!(Location::new(40, 13), follow::<AimPosition>(&directions));
+        assert_eq!(trajectory::<AimPosition>(&directions), Models::default().trajectory("aim", &directions).unwrap());
+    }
+
+    #[test]
+    fn register_models() {
+        // Sinks with every direction, and never moves forward.
+        #[derive(Default)]
+        struct Sink(i32);
+
+        impl Model for Sink {
+            fn follow(self, dir: &Direction) -> Self {
+                match dir {
+                    Direction::Forward(amount) | Direction::Back(amount) | Direction::Down(amount) | Direction::Up(amount) =>
+                        Sink(self.0 + amount),
+                }
+            }
+
+            fn location(&self) -> Location {
+                Location::new(self.0, 0)
+            }
+        }
+
+        let mut models = Models::default();
+        models.register::<Sink>("sink").register::<Sink>("aim");
+        assert_eq!(vec!["position", "aim", "sink"], models.names().collect::<Vec<&str>>());
+
+        let directions = vec![Direction::Forward(5), Direction::Up(3)];
+        assert_eq!(vec![Location::new(0, 0), Location::new(5, 0), Location::new(8, 0)], models.trajectory("aim", &directions).unwrap());
+        assert!(matches!(models.trajectory("missing", &directions), Err(Error::NoSolution(_))));
+    }
+
+    #[test]
+    fn load_scripts() {
+        let script = "\
+# Dive, then come back up.
+forward 5
+repeat 2 {
+  down 2   # Each repeat goes deeper.
+  repeat 2 {
+    forward 1
+  }
+}
+back 1
+";
+        let directions = load_script(script.as_bytes(), Mode::Strict).unwrap();
+        assert_eq!(vec![
+            Direction::Forward(5),
+            Direction::Down(2), Direction::Forward(1), Direction::Forward(1),
+            Direction::Down(2), Direction::Forward(1), Direction::Forward(1),
+            Direction::Back(1),
+        ], directions);
+        assert_eq!(Location::new(4, 8), follow::<Position>(&directions));
+
+        // Unknown commands are only skipped in lenient mode, but bad amounts are always errors.
+        assert_eq!(vec![Direction::Up(1)], load_script("sideways 3\nup 1\n".as_bytes(), Mode::Lenient).unwrap());
+        assert!(load_script("up x\n".as_bytes(), Mode::Lenient).is_err());
+
+        // Empty blocks don't add anything, however many times they repeat.
+        assert_eq!(Vec::<Direction>::new(), load_script("repeat 18446744073709551615 {\n  # Nothing to repeat.\n}\n".as_bytes(), Mode::Strict).unwrap());
+    }
+
+    #[test]
+    fn load_invalid_scripts() {
+        let error = |script: &str| match load_script(script.as_bytes(), Mode::Strict) {
+            Err(Error::Parse(e)) => e,
+            other => panic!("Expected a parse error, got {:?}", other),
+        };
+
+        assert_eq!(ParseError::new(3, "unknown direction 'sideways'").on_line(2), error("up 1\n  sideways 3\n"));
+        assert_eq!(ParseError::new(8, "expected a number, found 'x'").on_line(1), error("repeat x {\n}\n"));
+        assert_eq!(ParseError::new(1, "expected a block like 'repeat 3 {', found 'repeat 3'").on_line(1), error("repeat 3\n"));
+        assert_eq!(ParseError::new(3, "found '}' without a 'repeat' block").on_line(2), error("up 1\n  }\n"));
+        assert_eq!(ParseError::new(3, "expected at most 1000000 directions after repeating").on_line(2),
+                   error("forward 1\n  repeat 100000 {\n    repeat 100000 {\n      forward 1\n    }\n  }\n"));
+        assert_eq!(ParseError::new(1, "missing a '}' to close the 'repeat' block").on_line(1),
+                   error("repeat 2 {\n  repeat 2 {\n  up 1\n}\n"));
+    }
+}
@@ -0,0 +1,211 @@
+use std::fmt::{Display, Formatter};
+use std::io::BufRead;
+
+use crate::error::{Error, ParseError, Result};
+use crate::grid::{Grid, Point};
+use crate::solution::Solution;
+
+/// Number of pixels in an image enhancement algorithm, one for each 3x3 square of pixels.
+const ALGORITHM_LEN: usize = 512;
+
+/// Day 20: Trench Map.
+pub struct Day20;
+
+impl Solution for Day20 {
+    type Puzzle = TrenchMap;
+    type Part1 = usize;
+    type Part2 = usize;
+
+    fn load<R: BufRead>(reader: R) -> Result<Self::Puzzle> {
+        TrenchMap::load(reader)
+    }
+
+    fn part1(map: &Self::Puzzle) -> Result<Self::Part1> {
+        map.enhance(2).lit()
+    }
+
+    fn part2(map: &Self::Puzzle) -> Result<Self::Part2> {
+        map.enhance(50).lit()
+    }
+}
+
+/// TrenchMap is an image of the ocean trench, and the algorithm that enhances it.
+pub struct TrenchMap {
+    /// Whether the output pixel is lit for each 3x3 square of input pixels, read as a 9-bit
+    /// binary number from left to right, top to bottom.
+    pub algorithm: Vec<bool>,
+    pub image: Image,
+}
+
+impl TrenchMap {
+    /// Loads a TrenchMap from the given input, which has a line with the 512 pixels of the
+    /// algorithm, a blank line, and then the image.  Pixels are `#` when lit and `.` when dark.
+    pub fn load<R: BufRead>(mut reader: R) -> Result<Self> {
+        let mut line = String::new();
+        reader.read_line(&mut line)?;
+
+        let algorithm = line.trim_end().chars().enumerate()
+            .map(|(col, c)| parse_pixel(c)
+                .ok_or_else(|| ParseError::new(col + 1, format!("expected '#' or '.', found '{}'", c))))
+            .collect::<std::result::Result<Vec<bool>, ParseError>>()?;
+
+        if algorithm.len() != ALGORITHM_LEN {
+            return Err(ParseError::new(1, format!("expected {} pixels in the algorithm, found {}", ALGORITHM_LEN, algorithm.len())).into());
+        }
+
+        line.clear();
+        reader.read_line(&mut line)?;
+        if !line.trim().is_empty() {
+            return Err(ParseError::new(1, "expected a blank line after the algorithm").on_line(2).into());
+        }
+
+        let pixels = Grid::parse(reader, "'#' or '.'", parse_pixel)
+            .map_err(|e| e.after_lines(2))?;
+
+        Ok(TrenchMap { algorithm, image: Image { pixels, background: false } })
+    }
+
+    /// Returns the image after enhancing it the given number of times.
+    pub fn enhance(&self, steps: usize) -> Image {
+        let mut image = self.image.clone();
+        for _ in 0..steps {
+            image = image.enhance(&self.algorithm);
+        }
+
+        image
+    }
+}
+
+/// Image is a picture of lit and dark pixels that goes on forever in every direction.  Pixels
+/// outside of the grid all match the background.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct Image {
+    pixels: Grid<bool>,
+    background: bool,
+}
+
+impl Image {
+    /// Returns the number of lit pixels in this image, or an error if the background is lit and
+    /// there are infinitely many.
+    pub fn lit(&self) -> Result<usize> {
+        if self.background {
+            return Err(Error::no_solution("Infinitely many pixels are lit."));
+        }
+
+        Ok(self.pixels.iter().filter(|&(_, &lit)| lit).count())
+    }
+
+    /// Returns a new image, where each pixel is looked up in the algorithm from the 3x3 square
+    /// of pixels around it.  The grid grows by one pixel on each side, since those pixels can be
+    /// affected by pixels in this grid.  Every background pixel is surrounded by background, so
+    /// the new background is either the first or last pixel of the algorithm.
+    pub fn enhance(&self, algorithm: &[bool]) -> Image {
+        let pixels = Grid::from_fn(self.pixels.rows() + 2, self.pixels.cols() + 2, |p| {
+            // The new grid's point p is at p - 1 in this grid.
+            let mut index = 0;
+            for row in p.row as isize - 2..=p.row as isize {
+                for col in p.col as isize - 2..=p.col as isize {
+                    index = index << 1 | self.pixel(row, col) as usize;
+                }
+            }
+
+            algorithm[index]
+        });
+
+        let background = algorithm[if self.background { ALGORITHM_LEN - 1 } else { 0 }];
+
+        Image { pixels, background }
+    }
+
+    /// Returns whether the pixel at the given row and column is lit.
+    fn pixel(&self, row: isize, col: isize) -> bool {
+        if row < 0 || col < 0 {
+            return self.background;
+        }
+
+        *self.pixels.get(Point::new(row as usize, col as usize)).unwrap_or(&self.background)
+    }
+}
+
+impl Display for Image {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        for row in 0..self.pixels.rows() {
+            for col in 0..self.pixels.cols() {
+                write!(f, "{}", if self.pixels[Point::new(row, col)] { '#' } else { '.' })?;
+            }
+
+            writeln!(f)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Returns whether a pixel character is lit, or None if it isn't a pixel.
+fn parse_pixel(c: char) -> Option<bool> {
+    match c {
+        '#' => Some(true),
+        '.' => Some(false),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use super::*;
+
+    #[test]
+    fn test_sample() {
+        // The sample's algorithm lights every dark 3x3 square, so the background flickers.
+        let map = TrenchMap::load(fs::read_to_string("input/day20_sample.txt").unwrap().as_bytes()).unwrap();
+
+        assert!(matches!(map.enhance(1).lit(), Err(Error::NoSolution(_))));
+        assert_eq!(64, map.enhance(2).lit().unwrap());
+        assert_eq!(4997, map.enhance(50).lit().unwrap());
+    }
+
+    #[test]
+    fn test_sample2() {
+        // The puzzle's own example, where dark squares stay dark.
+        let map = TrenchMap::load(fs::read_to_string("input/day20_sample2.txt").unwrap().as_bytes()).unwrap();
+
+        assert_eq!(35, map.enhance(2).lit().unwrap());
+        assert_eq!(3351, map.enhance(50).lit().unwrap());
+    }
+
+    #[test]
+    fn enhance_identity() {
+        // Each pixel only depends on the middle of its 3x3 square, so the image stays the same.
+        let algorithm = (0..ALGORITHM_LEN).map(|i| if i & 0b10000 != 0 { '#' } else { '.' }).collect::<String>();
+        let map = TrenchMap::load(format!("{}\n\n#..\n.#.\n", algorithm).as_bytes()).unwrap();
+
+        let image = map.enhance(3);
+        assert_eq!(2, image.lit().unwrap());
+        assert_eq!("\
+.........
+.........
+.........
+...#.....
+....#....
+.........
+.........
+.........
+", image.to_string());
+    }
+
+    #[test]
+    fn load_invalid() {
+        match TrenchMap::load("#.#\n\n#.\n".as_bytes()) {
+            Err(Error::Parse(e)) => assert_eq!(ParseError::new(1, "expected 512 pixels in the algorithm, found 3"), e),
+            other => panic!("Expected a parse error, got {:?}", other.map(|map| map.image)),
+        }
+
+        let algorithm = ".".repeat(ALGORITHM_LEN);
+        match TrenchMap::load(format!("{}\n\n#.\n.x\n", algorithm).as_bytes()) {
+            Err(Error::Parse(e)) => assert_eq!(ParseError::new(2, "expected '#' or '.', found 'x'").on_line(4), e),
+            other => panic!("Expected a parse error, got {:?}", other.map(|map| map.image)),
+        }
+    }
+}
@@ -1,76 +1,437 @@
-#[allow(dead_code)]
-pub fn solution() {
-    println!("Part 1: {}", deterministic_score(8, 7));
-    println!("Part 2: {}", dirac_wins(8, 7));
+use std::collections::HashMap;
+use std::fmt::Write;
+use std::io::BufRead;
+use itertools::Itertools;
+
+use crate::error::{parse_num, Error, ParseError, Result};
+use crate::solution::Solution;
+
+/// Day 21: Dirac Dice.
+pub struct Day21;
+
+impl Solution for Day21 {
+    type Puzzle = Vec<i64>;
+    type Part1 = i64;
+    type Part2 = u128;
+
+    fn load<R: BufRead>(reader: R) -> Result<Self::Puzzle> {
+        load(reader)
+    }
+
+    fn part1(starts: &Self::Puzzle) -> Result<Self::Part1> {
+        deterministic_score(&Rules::DETERMINISTIC, starts)
+    }
+
+    fn part2(starts: &Self::Puzzle) -> Result<Self::Part2> {
+        dirac_wins(&Rules::DIRAC, starts)
+    }
 }
 
-/// Plays a game of dirac dice with a deterministic d100 (rolls 1, then 2, ...), and returns
-/// the score of the losing player by the number of times the die was rolled during the game.
-/// Dirac dice is played by rolling a die 3 times, then moving a pawn that many times
-/// around a board marked 1..=10.  The square the player lands on is added to the player's score.
-/// The game ends as a win for any player whose score reaches at least 1000.
-fn deterministic_score(player1_start: i64, player2_start: i64) -> i64 {
-    let mut player1_score = 0;
-    let mut player1_space = player1_start;
+/// Rules are the settings for a game of dirac dice.  Each turn, a player rolls a die with
+/// `die_sides` sides `rolls_per_turn` times, moves their pawn that many spaces around a board
+/// marked `1..=board_size`, and adds the space they land on to their score.  The first player
+/// whose score reaches at least `winning_score` wins.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub struct Rules {
+    pub board_size: i64,
+    pub die_sides: i64,
+    pub rolls_per_turn: usize,
+    pub winning_score: i64,
+    pub players: usize,
+}
 
-    let mut player2_score = 0;
-    let mut player2_space = player2_start;
+impl Rules {
+    /// Rules of part 1, played with a deterministic d100.
+    pub const DETERMINISTIC: Rules = Rules { board_size: 10, die_sides: 100, rolls_per_turn: 3, winning_score: 1000, players: 2 };
 
-    let mut die = DeterministicD100::default();
-    let mut rolls = 0;
+    /// Rules of part 2, played with a dirac d3.
+    pub const DIRAC: Rules = Rules { board_size: 10, die_sides: 3, rolls_per_turn: 3, winning_score: 21, players: 2 };
 
-    loop {
-        rolls += 3;
-        if turn(&mut die, &mut player1_space, &mut player1_score) {
-            return rolls * player2_score;
+    /// Returns an error if these rules can't be played, or if the starting positions don't
+    /// match them.
+    fn check(&self, starts: &[i64]) -> Result<()> {
+        if self.board_size < 1 || self.die_sides < 1 || self.rolls_per_turn < 1 || self.winning_score < 1 || self.players < 1 {
+            return Err(Error::no_solution(format!("Rules need at least one space, side, roll, point, and player: {:?}", self)));
         }
 
-        rolls += 3;
-        if turn(&mut die, &mut player2_space, &mut player2_score) {
-            return rolls * player1_score;
+        if starts.len() != self.players {
+            return Err(Error::no_solution(format!("Expected {} starting positions, found {}.", self.players, starts.len())));
         }
+
+        match starts.iter().find(|&&start| !(1..=self.board_size).contains(&start)) {
+            Some(start) => Err(Error::no_solution(format!("Positions range from 1 to {}, found {}.", self.board_size, start))),
+            None => Ok(()),
+        }
+    }
+
+    /// Returns the space a pawn lands on after moving the given number of spaces.
+    fn advance(&self, space: i64, spaces: i64) -> i64 {
+        (space - 1 + spaces) % self.board_size + 1
     }
 }
 
-fn turn(die: &mut DeterministicD100, space: &mut i64, score: &mut i64) -> bool {
-    *space = (((*space - 1) + die.next() + die.next() + die.next()) % 10) + 1;
-    *score += *space;
+/// Loads the starting positions of each player from the given input, which contains lines like
+/// 'Player 1 starting position: 8'.  Positions only need to be positive here, since the size of
+/// the board depends on the rules that the game is played with.
+pub fn load<R: BufRead>(reader: R) -> Result<Vec<i64>> {
+    let mut starts = Vec::new();
+
+    for (i, line) in reader.lines().enumerate() {
+        let line = line?;
+        let line = line.trim_end();
+        if line.is_empty() {
+            continue;
+        }
+
+        let error = |e: ParseError| e.on_line(i + 1);
+
+        let (_, token) = line.split_once("starting position: ")
+            .ok_or_else(|| error(ParseError::new(1, format!("expected a line like 'Player 1 starting position: 8', found '{}'", line))))?;
+        let position: i64 = parse_num(line, token).map_err(error)?;
+
+        if position < 1 {
+            return Err(error(ParseError::at(line, token, format!("expected a positive position, found {}", position))).into());
+        }
+
+        starts.push(position);
+    }
+
+    if starts.is_empty() {
+        return Err(ParseError::new(1, "expected at least one player").into());
+    }
+
+    Ok(starts)
+}
+
+/// Plays a game of dirac dice with a deterministic die (rolls 1, then 2, ..., wrapping back to 1
+/// after the highest side), and returns the lowest score of the losing players multiplied by the
+/// number of times the die was rolled during the game.
+pub fn deterministic_score(rules: &Rules, starts: &[i64]) -> Result<i64> {
+    rules.check(starts)?;
+
+    let mut spaces = starts.to_vec();
+    let mut scores = vec![0; starts.len()];
 
-    *score >= 1000
+    let mut die = DeterministicDie::new(rules.die_sides);
+    let mut rolls = 0;
+
+    for player in (0..starts.len()).cycle() {
+        let spaces_moved = (0..rules.rolls_per_turn).map(|_| die.next()).sum();
+        rolls += rules.rolls_per_turn as i64;
+
+        spaces[player] = rules.advance(spaces[player], spaces_moved);
+        scores[player] += spaces[player];
+
+        if scores[player] >= rules.winning_score {
+            let losing_score = scores.iter().enumerate()
+                .filter(|&(other, _)| other != player)
+                .map(|(_, &score)| score)
+                .min()
+                .unwrap_or(0);
+
+            return Ok(rolls * losing_score);
+        }
+    }
+
+    unreachable!("Players take turns until someone wins")
 }
 
 #[derive(Debug)]
-struct DeterministicD100 {
+struct DeterministicDie {
+    sides: i64,
     next: i64,
 }
 
-impl Default for DeterministicD100 {
-    fn default() -> Self {
-        DeterministicD100 { next: 1 }
+impl DeterministicDie {
+    fn new(sides: i64) -> Self {
+        DeterministicDie { sides, next: 1 }
     }
-}
 
-impl DeterministicD100 {
     fn next(&mut self) -> i64 {
         let value = self.next;
-        self.next = self.next % 100 + 1;
+        self.next = self.next % self.sides + 1;
         value
     }
 }
 
 /// Plays a game of dirac dice with a dirac die, and returns the number of universes in which
-/// the winning player won.  A dirac die is a D3 that splits the universe into all possible
-/// outcomes when it's rolled.  The game ends when either player's score reaches at least 21.
-fn dirac_wins(player1_start: i64, player2_start: i64) -> usize {
-    todo!()
+/// the winning player won.  A dirac die splits the universe into all possible outcomes when
+/// it's rolled.
+pub fn dirac_wins(rules: &Rules, starts: &[i64]) -> Result<u128> {
+    Ok(dirac_universes(rules, starts)?.into_iter().max().unwrap_or(0))
+}
+
+/// Plays a game of dirac dice with a dirac die, and returns the number of universes that each
+/// player wins in.
+pub fn dirac_universes(rules: &Rules, starts: &[i64]) -> Result<Vec<u128>> {
+    Ok(dirac_odds(rules, starts)?.wins)
+}
+
+/// Odds are the outcome of every universe of a game of dirac dice from some starting positions.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Odds {
+    /// Starting position of each player.
+    pub starts: Vec<i64>,
+    /// Number of universes that each player wins in.
+    pub wins: Vec<u128>,
+    /// Chance that each player wins.  Universes where the game runs longer split more times, so
+    /// these aren't proportional to `wins`.
+    pub probabilities: Vec<f64>,
+    /// Expected number of turns, counting every player's turns, until someone wins.
+    pub expected_turns: f64,
+}
+
+/// Plays a game of dirac dice with a dirac die, and returns the odds of each player winning.
+pub fn dirac_odds(rules: &Rules, starts: &[i64]) -> Result<Odds> {
+    rules.check(starts)?;
+
+    Ok(DiracGame::new(rules).odds(starts))
+}
+
+/// Returns the odds for every combination of starting positions, ordered by player 1's start,
+/// then player 2's, and so on.
+pub fn odds_table(rules: &Rules) -> Result<Vec<Odds>> {
+    rules.check(&vec![1; rules.players])?;
+
+    // Games from different starts reach many of the same states, so they share a game.
+    let mut game = DiracGame::new(rules);
+
+    Ok((0..rules.players)
+        .map(|_| 1..=rules.board_size)
+        .multi_cartesian_product()
+        .map(|starts| game.odds(&starts))
+        .collect())
+}
+
+/// Returns a table of the chance that player 1 wins, and of the expected number of turns, with
+/// a row for each of player 1's starts and a column for each of player 2's starts.  Returns an
+/// error unless the table comes from `odds_table` for a two-player game.
+pub fn odds_matrix(table: &[Odds]) -> Result<String> {
+    if let Some(odds) = table.iter().find(|odds| odds.starts.len() != 2) {
+        return Err(Error::no_solution(format!("Odds matrices need a two-player game, found {} players.", odds.starts.len())));
+    }
+
+    let size = table.iter().map(|odds| odds.starts[1]).max().unwrap_or(0) as usize;
+    if size == 0 || table.len() != size * size {
+        return Err(Error::no_solution("Odds matrices need the odds for every pair of starting positions."));
+    }
+
+    let mut matrix = String::new();
+
+    let mut write_matrix = |title: &str, cell: &dyn Fn(&Odds) -> String| {
+        writeln!(matrix, "{}", title).unwrap();
+        write!(matrix, "{:>5}", "").unwrap();
+        for start in 1..=size {
+            write!(matrix, "  {:>6}", start).unwrap();
+        }
+        matrix.push('\n');
+
+        for (i, row) in table.chunks(size).enumerate() {
+            write!(matrix, "{:>5}", i + 1).unwrap();
+            for odds in row {
+                write!(matrix, "  {:>6}", cell(odds)).unwrap();
+            }
+            matrix.push('\n');
+        }
+    };
+
+    write_matrix("Chance that player 1 wins, by player 1's start (rows) and player 2's start (columns):",
+                 &|odds| format!("{:.1}%", odds.probabilities[0] * 100.0));
+    write_matrix("\nExpected number of turns:", &|odds| format!("{:.2}", odds.expected_turns));
+
+    Ok(matrix)
+}
+
+/// Returns the odds as CSV, with one row per combination of starting positions.
+pub fn odds_csv(table: &[Odds]) -> String {
+    let players = table.first().map_or(0, |odds| odds.starts.len());
+
+    let mut columns = Vec::new();
+    columns.extend((1..=players).map(|player| format!("start{}", player)));
+    columns.extend((1..=players).map(|player| format!("wins{}", player)));
+    columns.extend((1..=players).map(|player| format!("probability{}", player)));
+    columns.push("expected_turns".to_string());
+
+    let mut csv = format!("{}\n", columns.join(","));
+    for odds in table {
+        let mut row = Vec::new();
+        row.extend(odds.starts.iter().map(|start| start.to_string()));
+        row.extend(odds.wins.iter().map(|wins| wins.to_string()));
+        row.extend(odds.probabilities.iter().map(|p| format!("{:.6}", p)));
+        row.push(format!("{:.6}", odds.expected_turns));
+
+        writeln!(csv, "{}", row.join(",")).unwrap();
+    }
+
+    csv
+}
+
+/// Returns the number of ways that each total can be rolled on a turn, as (total, ways) pairs.
+fn roll_totals(rules: &Rules) -> Vec<(i64, u128)> {
+    let mut ways: HashMap<i64, u128> = HashMap::from([(0, 1)]);
+
+    for _ in 0..rules.rolls_per_turn {
+        let mut next = HashMap::new();
+        for (total, count) in ways {
+            for side in 1..=rules.die_sides {
+                *next.entry(total + side).or_insert(0) += count;
+            }
+        }
+
+        ways = next;
+    }
+
+    let mut totals = ways.into_iter().collect::<Vec<(i64, u128)>>();
+    totals.sort_unstable();
+    totals
+}
+
+/// Player is a pawn's space on the board, and the player's score.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+struct Player {
+    space: i64,
+    score: i64,
+}
+
+/// Outcome is what happens in every universe that splits off from a game's state, with each
+/// player's numbers in turn order starting with the player about to roll.
+#[derive(Debug, Clone)]
+struct Outcome {
+    wins: Vec<u128>,
+    probabilities: Vec<f64>,
+    turns: f64,
+}
+
+/// `DiracGame` works out the outcomes of a game of dirac dice.  Games that reach the same state
+/// are only played once, and memoized in `outcomes`.
+struct DiracGame<'a> {
+    rules: &'a Rules,
+    /// Each total that can be rolled in a turn, with the number of ways to roll it.
+    rolls: Vec<(i64, u128)>,
+    /// Outcomes keyed by the players in turn order.
+    outcomes: HashMap<Vec<Player>, Outcome>,
+}
+
+impl<'a> DiracGame<'a> {
+    fn new(rules: &'a Rules) -> Self {
+        DiracGame { rules, rolls: roll_totals(rules), outcomes: HashMap::new() }
+    }
+
+    /// Returns the odds of a game from the given starting positions.
+    fn odds(&mut self, starts: &[i64]) -> Odds {
+        let players = starts.iter().map(|&space| Player { space, score: 0 }).collect::<Vec<Player>>();
+        let outcome = self.turn(&players);
+
+        Odds { starts: starts.to_vec(), wins: outcome.wins, probabilities: outcome.probabilities, expected_turns: outcome.turns }
+    }
+
+    /// Returns the outcome of the game where `players` are in turn order, starting with the
+    /// player about to roll.
+    fn turn(&mut self, players: &[Player]) -> Outcome {
+        if let Some(outcome) = self.outcomes.get(players) {
+            return outcome.clone();
+        }
+
+        let n = players.len();
+        let total_ways = self.rolls.iter().map(|&(_, ways)| ways).sum::<u128>() as f64;
+        let mut outcome = Outcome { wins: vec![0; n], probabilities: vec![0.0; n], turns: 1.0 };
+
+        for i in 0..self.rolls.len() {
+            let (spaces, universes) = self.rolls[i];
+            let chance = universes as f64 / total_ways;
+            let space = self.rules.advance(players[0].space, spaces);
+            let moved = Player { space, score: players[0].score + space };
+
+            if moved.score >= self.rules.winning_score {
+                outcome.wins[0] += universes;
+                outcome.probabilities[0] += chance;
+                continue;
+            }
+
+            // The next player takes their turn, so the player who just moved goes last.
+            let mut next = players[1..].to_vec();
+            next.push(moved);
+
+            let next_outcome = self.turn(&next);
+            for player in 0..n {
+                let next_player = (player + n - 1) % n;
+                outcome.wins[player] += universes * next_outcome.wins[next_player];
+                outcome.probabilities[player] += chance * next_outcome.probabilities[next_player];
+            }
+            outcome.turns += chance * next_outcome.turns;
+        }
+
+        self.outcomes.insert(players.to_vec(), outcome.clone());
+        outcome
+    }
 }
 
 #[test]
 fn deterministic_sample() {
-    assert_eq!(739785, deterministic_score(4, 8));
+    assert_eq!(739785, deterministic_score(&Rules::DETERMINISTIC, &[4, 8]).unwrap());
 }
 
 #[test]
 fn dirac_sample() {
-    assert_eq!(444356092776315, dirac_wins(4, 8));
-}
\ No newline at end of file
+    assert_eq!(444356092776315, dirac_wins(&Rules::DIRAC, &[4, 8]).unwrap());
+    assert_eq!(vec![444356092776315, 341960390180808], dirac_universes(&Rules::DIRAC, &[4, 8]).unwrap());
+}
+
+#[test]
+fn variant_rules() {
+    // Three players flip a coin on a 2-space board.  Moving 2 spaces lands back on the same
+    // space, and landing on space 2 wins.
+    let rules = Rules { board_size: 2, die_sides: 2, rolls_per_turn: 1, winning_score: 2, players: 3 };
+    assert_eq!(vec![3, 1, 1], dirac_universes(&rules, &[2, 1, 2]).unwrap());
+    assert_eq!(4, deterministic_score(&rules, &[2, 1, 2]).unwrap());
+
+    let odds = dirac_odds(&rules, &[2, 1, 2]).unwrap();
+    assert_eq!(vec![0.625, 0.25, 0.125], odds.probabilities);
+    assert_eq!(1.875, odds.expected_turns);
+
+    assert!(matches!(deterministic_score(&rules, &[1, 1]), Err(Error::NoSolution(_))));
+    assert!(matches!(dirac_wins(&Rules::DIRAC, &[4, 11]), Err(Error::NoSolution(_))));
+}
+
+#[test]
+fn odds_tables() {
+    let table = odds_table(&Rules::DIRAC).unwrap();
+    assert_eq!(100, table.len());
+
+    let odds = &table[3 * 10 + 7];
+    assert_eq!(vec![4, 8], odds.starts);
+    assert_eq!(vec![444356092776315, 341960390180808], odds.wins);
+    assert!((odds.probabilities.iter().sum::<f64>() - 1.0).abs() < 1e-9);
+
+    // Everyone scores 1 point a turn on a 1-space board, so the first player always wins.
+    let rules = Rules { board_size: 1, die_sides: 2, rolls_per_turn: 1, winning_score: 2, players: 2 };
+    let table = odds_table(&rules).unwrap();
+    assert_eq!("start1,start2,wins1,wins2,probability1,probability2,expected_turns\n1,1,8,0,1.000000,0.000000,3.000000\n",
+               odds_csv(&table));
+    assert_eq!("\
+Chance that player 1 wins, by player 1's start (rows) and player 2's start (columns):
+            1
+    1  100.0%
+
+Expected number of turns:
+            1
+    1    3.00
+", odds_matrix(&table).unwrap());
+
+    let rules = Rules { players: 1, ..rules };
+    assert!(matches!(odds_matrix(&odds_table(&rules).unwrap()), Err(Error::NoSolution(_))));
+    assert!(matches!(odds_matrix(&[]), Err(Error::NoSolution(_))));
+}
+
+#[test]
+fn load_invalid() {
+    match load("Player 1 starting position: 4\nPlayer 2 starting position: 0\n".as_bytes()) {
+        Err(crate::error::Error::Parse(e)) => assert_eq!(ParseError { line: 2, column: 29, message: "expected a positive position, found 0".to_string() }, e),
+        other => panic!("Expected a parse error, got {:?}", other),
+    }
+
+    // Positions past the end of the board are up to the rules.
+    let starts = load("Player 1 starting position: 4\nPlayer 2 starting position: 11\n".as_bytes()).unwrap();
+    assert!(matches!(dirac_wins(&Rules::DIRAC, &starts), Err(crate::error::Error::NoSolution(_))));
+    assert!(dirac_wins(&Rules { board_size: 12, ..Rules::DIRAC }, &starts).is_ok());
+}
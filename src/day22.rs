@@ -0,0 +1,210 @@
+use std::io::BufRead;
+use std::str::FromStr;
+
+use crate::error::{parse_lines, parse_num, ParseError, Result};
+use crate::solution::Solution;
+
+/// Day 22: Reactor Reboot.
+pub struct Day22;
+
+impl Solution for Day22 {
+    type Puzzle = Vec<Step>;
+    type Part1 = i64;
+    type Part2 = i64;
+
+    fn load<R: BufRead>(reader: R) -> Result<Self::Puzzle> {
+        parse_lines(reader)
+    }
+
+    fn part1(steps: &Self::Puzzle) -> Result<Self::Part1> {
+        Ok(cubes_on(steps, Some(&Cuboid::INITIALIZATION)))
+    }
+
+    fn part2(steps: &Self::Puzzle) -> Result<Self::Part2> {
+        Ok(cubes_on(steps, None))
+    }
+}
+
+/// Cuboid is a box of cubes in the reactor, including the cubes at both ends of each range.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub struct Cuboid {
+    /// Smallest x, y, and z coordinates in the cuboid.
+    pub min: [i64; 3],
+    /// Largest x, y, and z coordinates in the cuboid.
+    pub max: [i64; 3],
+}
+
+impl Cuboid {
+    /// Region of the reactor that's rebooted during initialization.
+    pub const INITIALIZATION: Cuboid = Cuboid { min: [-50; 3], max: [50; 3] };
+
+    /// Returns the number of cubes in this cuboid.
+    pub fn volume(&self) -> i64 {
+        (0..3).map(|axis| self.max[axis] - self.min[axis] + 1).product()
+    }
+
+    /// Returns the cubes that are in both this cuboid and the other cuboid, or None if they
+    /// don't overlap.
+    pub fn intersection(&self, other: &Cuboid) -> Option<Cuboid> {
+        let mut overlap = Cuboid { min: [0; 3], max: [0; 3] };
+
+        for axis in 0..3 {
+            overlap.min[axis] = self.min[axis].max(other.min[axis]);
+            overlap.max[axis] = self.max[axis].min(other.max[axis]);
+
+            if overlap.min[axis] > overlap.max[axis] {
+                return None;
+            }
+        }
+
+        Some(overlap)
+    }
+}
+
+/// Step is a reboot step that turns every cube in a cuboid on or off.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub struct Step {
+    pub on: bool,
+    pub cuboid: Cuboid,
+}
+
+impl FromStr for Step {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        // Step looks like 'on x=10..12,y=10..12,z=10..12'
+        let invalid = || ParseError::new(1, format!("expected a step like 'on x=10..12,y=10..12,z=10..12', found '{}'", s));
+
+        let (state, ranges) = s.trim().split_once(' ').ok_or_else(invalid)?;
+        let on = match state {
+            "on" => true,
+            "off" => false,
+            _ => return Err(invalid()),
+        };
+
+        let ranges = ranges.split(',').collect::<Vec<&str>>();
+        if ranges.len() != 3 {
+            return Err(invalid());
+        }
+
+        let mut cuboid = Cuboid { min: [0; 3], max: [0; 3] };
+        for (axis, (range, name)) in ranges.into_iter().zip(["x=", "y=", "z="]).enumerate() {
+            let (min, max) = range.strip_prefix(name)
+                .and_then(|range| range.split_once(".."))
+                .ok_or_else(invalid)?;
+
+            cuboid.min[axis] = parse_num(s, min)?;
+            cuboid.max[axis] = parse_num(s, max)?;
+
+            if cuboid.min[axis] > cuboid.max[axis] {
+                return Err(ParseError::at(s, min, format!("expected a range from low to high, found {}..{}", cuboid.min[axis], cuboid.max[axis])));
+            }
+        }
+
+        Ok(Step { on, cuboid })
+    }
+}
+
+/// Runs the reboot steps, and returns the number of cubes that are on at the end.  If a region
+/// is given, only cubes inside of it are counted.
+pub fn cubes_on(steps: &[Step], region: Option<&Cuboid>) -> i64 {
+    // Cuboids are counted with inclusion-exclusion: each cuboid adds or removes its volume, and
+    // overlaps are corrected by adding the overlap with the opposite sign.
+    let mut counted: Vec<(Cuboid, i64)> = Vec::new();
+
+    for step in steps {
+        let cuboid = match region {
+            Some(region) => match step.cuboid.intersection(region) {
+                Some(cuboid) => cuboid,
+                None => continue,
+            },
+            None => step.cuboid,
+        };
+
+        let overlaps = counted.iter()
+            .filter_map(|(other, sign)| cuboid.intersection(other).map(|overlap| (overlap, -sign)))
+            .collect::<Vec<(Cuboid, i64)>>();
+        counted.extend(overlaps);
+
+        if step.on {
+            counted.push((cuboid, 1));
+        }
+    }
+
+    counted.iter().map(|(cuboid, sign)| cuboid.volume() * sign).sum()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+    use std::fs;
+
+    use super::*;
+    use crate::error::Error;
+
+    #[test]
+    fn test_sample() {
+        let steps: Vec<Step> = parse_lines(fs::read_to_string("input/day22_sample.txt").unwrap().as_bytes()).unwrap();
+
+        assert_eq!(39, cubes_on(&steps, Some(&Cuboid::INITIALIZATION)));
+        assert_eq!(39, cubes_on(&steps, None));
+    }
+
+    #[test]
+    fn outside_initialization() {
+        let steps: Vec<Step> = parse_lines("on x=-60..60,y=0..0,z=0..0\noff x=55..100,y=-5..5,z=-1..1\n".as_bytes()).unwrap();
+
+        assert_eq!(101, cubes_on(&steps, Some(&Cuboid::INITIALIZATION)));
+        assert_eq!(115, cubes_on(&steps, None));
+    }
+
+    #[test]
+    fn matches_voxels() {
+        // Compare against turning individual cubes on and off, with steps that overlap in
+        // several ways: nested, crossing, touching at an edge, and turning off then back on.
+        let steps: Vec<Step> = parse_lines("\
+on x=-2..2,y=-2..2,z=-2..2
+on x=0..4,y=1..3,z=-1..5
+off x=-1..1,y=-1..1,z=-1..1
+on x=-3..-2,y=-3..0,z=0..0
+off x=1..6,y=2..2,z=-5..5
+on x=0..0,y=0..0,z=0..0
+on x=-1..3,y=-1..3,z=2..3
+off x=-5..5,y=-5..-2,z=-5..5
+on x=2..5,y=-1..4,z=-3..0
+".as_bytes()).unwrap();
+
+        let mut voxels = HashSet::new();
+        for step in &steps {
+            let Cuboid { min, max } = step.cuboid;
+            for x in min[0]..=max[0] {
+                for y in min[1]..=max[1] {
+                    for z in min[2]..=max[2] {
+                        if step.on {
+                            voxels.insert((x, y, z));
+                        } else {
+                            voxels.remove(&(x, y, z));
+                        }
+                    }
+                }
+            }
+        }
+
+        assert_eq!(voxels.len() as i64, cubes_on(&steps, None));
+    }
+
+    #[test]
+    fn parse_invalid() {
+        assert_eq!(Err(ParseError::new(15, "expected a range from low to high, found 12..10")),
+                   "on x=10..12,y=12..10,z=10..12".parse::<Step>());
+        assert_eq!(Err(ParseError::new(25, "expected a number, found '1x'")),
+                   "off x=10..12,y=10..12,z=1x..12".parse::<Step>());
+        assert!("on x=10..12,y=10..12".parse::<Step>().is_err());
+        assert!("on x=10..12,z=10..12,y=10..12".parse::<Step>().is_err());
+
+        match parse_lines::<_, Step>("on x=1..2,y=1..2,z=1..2\ntoggle x=1..2,y=1..2,z=1..2\n".as_bytes()) {
+            Err(Error::Parse(e)) => assert_eq!(2, e.line),
+            other => panic!("Expected a parse error, got {:?}", other),
+        }
+    }
+}
@@ -0,0 +1,339 @@
+use std::cmp::Ordering;
+use std::collections::{BinaryHeap, HashMap};
+use std::fmt::{Display, Formatter};
+use std::io::BufRead;
+
+use crate::error::{Error, ParseError, Result};
+use crate::solution::Solution;
+
+/// Number of spaces in the hallway.
+const HALL_LEN: usize = 11;
+
+/// Number of side rooms, one for each type of amphipod.
+const ROOMS: usize = 4;
+
+/// Most amphipods that fit in a side room.
+const MAX_DEPTH: usize = 4;
+
+/// Empty space in the hallway or a side room.
+const EMPTY: u8 = 0;
+
+/// Rows that are hidden in the folded-up diagram, which go between the first and second rows
+/// of the side rooms.
+const UNFOLDED: [&str; 2] = ["DCBA", "DBAC"];
+
+/// Day 23: Amphipod.
+pub struct Day23;
+
+impl Solution for Day23 {
+    type Puzzle = Burrow;
+    type Part1 = usize;
+    type Part2 = usize;
+
+    fn load<R: BufRead>(reader: R) -> Result<Self::Puzzle> {
+        Burrow::load(reader)
+    }
+
+    fn part1(burrow: &Self::Puzzle) -> Result<Self::Part1> {
+        burrow.organize()
+    }
+
+    fn part2(burrow: &Self::Puzzle) -> Result<Self::Part2> {
+        burrow.unfold()?.organize()
+    }
+}
+
+/// Burrow is where each amphipod is: in the hallway, or in one of the side rooms.  Amphipods are
+/// numbered 1 to 4 for A to D, and empty spaces are 0.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub struct Burrow {
+    hall: [u8; HALL_LEN],
+    /// Amphipods in each side room, from the one nearest the hallway to the one at the back.
+    rooms: [[u8; MAX_DEPTH]; ROOMS],
+    depth: usize,
+}
+
+impl Burrow {
+    /// Loads a Burrow from the given diagram, like
+    ///
+    /// ```text
+    /// #############
+    /// #...........#
+    /// ###B#C#B#D###
+    ///   #A#D#C#A#
+    ///   #########
+    /// ```
+    pub fn load<R: BufRead>(reader: R) -> Result<Self> {
+        let lines = reader.lines().collect::<std::io::Result<Vec<String>>>()?;
+        let error = |line: usize, column: usize, message: String| ParseError::new(column, message).on_line(line + 1);
+
+        let hall_line = lines.get(1).map(|line| line.as_str()).unwrap_or("");
+        if hall_line.chars().count() < HALL_LEN + 2 {
+            return Err(error(1, 1, format!("expected a hallway like '#...........#', found '{}'", hall_line)).into());
+        }
+
+        let mut burrow = Burrow { hall: [EMPTY; HALL_LEN], rooms: [[EMPTY; MAX_DEPTH]; ROOMS], depth: 0 };
+        for (i, c) in hall_line.chars().skip(1).take(HALL_LEN).enumerate() {
+            burrow.hall[i] = parse_space(c).ok_or_else(|| error(1, i + 2, format!("expected an amphipod or '.', found '{}'", c)))?;
+        }
+
+        // Side rooms are below the hallway, until the wall at the bottom.
+        for (i, line) in lines.iter().enumerate().skip(2) {
+            let chars = line.chars().collect::<Vec<char>>();
+            if chars.iter().all(|&c| c == '#' || c == ' ') {
+                break;
+            }
+
+            if burrow.depth == MAX_DEPTH {
+                return Err(error(i, 1, format!("expected side rooms at most {} deep", MAX_DEPTH)).into());
+            }
+
+            for room in 0..ROOMS {
+                let column = Self::door(room) + 1;
+                let c = chars.get(column).copied().unwrap_or(' ');
+                burrow.rooms[room][burrow.depth] = parse_space(c)
+                    .ok_or_else(|| error(i, column + 1, format!("expected an amphipod or '.', found '{}'", c)))?;
+            }
+
+            burrow.depth += 1;
+        }
+
+        if burrow.depth == 0 {
+            return Err(error(2, 1, "expected at least one row of side rooms".to_string()).into());
+        }
+
+        Ok(burrow)
+    }
+
+    /// Returns this burrow with the two rows that were folded up in the diagram inserted
+    /// between the first and second rows of the side rooms.
+    pub fn unfold(&self) -> Result<Burrow> {
+        if self.depth + UNFOLDED.len() > MAX_DEPTH {
+            return Err(Error::no_solution(format!("Side rooms can't be more than {} deep.", MAX_DEPTH)));
+        }
+
+        let mut unfolded = *self;
+        for room in 0..ROOMS {
+            let mut spaces = self.rooms[room][..self.depth].to_vec();
+            for (i, row) in UNFOLDED.iter().enumerate() {
+                spaces.insert(1 + i, parse_space(row.as_bytes()[room] as char).unwrap());
+            }
+
+            unfolded.rooms[room][..spaces.len()].copy_from_slice(&spaces);
+        }
+        unfolded.depth += UNFOLDED.len();
+
+        Ok(unfolded)
+    }
+
+    /// Returns the least energy needed to move every amphipod into its own side room, with A in
+    /// the leftmost room and D in the rightmost.
+    pub fn organize(&self) -> Result<usize> {
+        // Dijkstra's algorithm over the arrangements of the burrow.
+        let mut explore = BinaryHeap::new();
+        explore.push(ToExplore { burrow: *self, energy: 0 });
+
+        let mut least_energy = HashMap::new();
+        least_energy.insert(*self, 0);
+
+        while let Some(ToExplore { burrow, energy }) = explore.pop() {
+            if burrow.is_organized() {
+                return Ok(energy);
+            }
+
+            if energy > least_energy[&burrow] {
+                continue;
+            }
+
+            for (next, cost) in burrow.moves() {
+                let next_energy = energy + cost;
+                if next_energy < *least_energy.get(&next).unwrap_or(&usize::MAX) {
+                    least_energy.insert(next, next_energy);
+                    explore.push(ToExplore { burrow: next, energy: next_energy });
+                }
+            }
+        }
+
+        Err(Error::no_solution("The amphipods can't be organized."))
+    }
+
+    /// Returns whether every amphipod is in its own side room.
+    fn is_organized(&self) -> bool {
+        (0..ROOMS).all(|room| self.rooms[room][..self.depth].iter().all(|&amphipod| amphipod as usize == room + 1))
+    }
+
+    /// Returns every burrow that one move of an amphipod leads to, with the energy it takes.
+    /// Amphipods either move out of a side room and stop in the hallway, or move from the
+    /// hallway into their own side room once only their own kind are in it.
+    fn moves(&self) -> Vec<(Burrow, usize)> {
+        let mut moves = Vec::new();
+
+        // Move out of side rooms that have amphipods that belong somewhere else.
+        for room in 0..ROOMS {
+            if self.is_settled(room) {
+                continue;
+            }
+
+            let depth = match self.rooms[room][..self.depth].iter().position(|&space| space != EMPTY) {
+                Some(depth) => depth,
+                None => continue,
+            };
+            let amphipod = self.rooms[room][depth];
+
+            for hall in 0..HALL_LEN {
+                if Self::is_door(hall) || !self.is_clear(Self::door(room), hall) {
+                    continue;
+                }
+
+                let mut next = *self;
+                next.rooms[room][depth] = EMPTY;
+                next.hall[hall] = amphipod;
+
+                let steps = depth + 1 + Self::door(room).abs_diff(hall);
+                moves.push((next, steps * energy(amphipod)));
+            }
+        }
+
+        // Move from the hallway into side rooms that are ready for them.
+        for hall in 0..HALL_LEN {
+            let amphipod = self.hall[hall];
+            if amphipod == EMPTY {
+                continue;
+            }
+
+            let room = amphipod as usize - 1;
+            let door = Self::door(room);
+            let path_start = if door < hall { hall - 1 } else { hall + 1 };
+            if !self.is_settled(room) || !self.is_clear(path_start, door) {
+                continue;
+            }
+
+            // Settled rooms fill from the back, so the amphipod goes to the last empty space.
+            let depth = match self.rooms[room][..self.depth].iter().rposition(|&space| space == EMPTY) {
+                Some(depth) => depth,
+                None => continue,
+            };
+
+            let mut next = *self;
+            next.hall[hall] = EMPTY;
+            next.rooms[room][depth] = amphipod;
+
+            let steps = hall.abs_diff(door) + depth + 1;
+            moves.push((next, steps * energy(amphipod)));
+        }
+
+        moves
+    }
+
+    /// Returns whether the side room only has amphipods that belong in it.
+    fn is_settled(&self, room: usize) -> bool {
+        self.rooms[room][..self.depth].iter().all(|&space| space == EMPTY || space as usize == room + 1)
+    }
+
+    /// Returns whether every hallway space from `from` to `to`, including both ends, is empty.
+    fn is_clear(&self, from: usize, to: usize) -> bool {
+        self.hall[from.min(to)..=from.max(to)].iter().all(|&space| space == EMPTY)
+    }
+
+    /// Returns the hallway space outside of the given side room's door.
+    fn door(room: usize) -> usize {
+        2 + room * 2
+    }
+
+    /// Returns whether the hallway space is outside of a door, where amphipods can't stop.
+    fn is_door(hall: usize) -> bool {
+        (0..ROOMS).any(|room| Self::door(room) == hall)
+    }
+}
+
+impl Display for Burrow {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "{}", "#".repeat(HALL_LEN + 2))?;
+        writeln!(f, "#{}#", self.hall.iter().map(|&space| space_char(space)).collect::<String>())?;
+
+        for depth in 0..self.depth {
+            let (edge, end) = if depth == 0 { ("###", "###") } else { ("  #", "#") };
+            let spaces = (0..ROOMS).map(|room| space_char(self.rooms[room][depth]).to_string()).collect::<Vec<String>>();
+            writeln!(f, "{}{}{}", edge, spaces.join("#"), end)?;
+        }
+
+        writeln!(f, "  {}", "#".repeat(ROOMS * 2 + 1))
+    }
+}
+
+/// `ToExplore` is a burrow that Dijkstra's algorithm has reached, with the energy it took to get
+/// there.  Burrows that took less energy are explored first.
+#[derive(Debug, Eq, PartialEq)]
+struct ToExplore {
+    burrow: Burrow,
+    energy: usize,
+}
+
+impl Ord for ToExplore {
+    fn cmp(&self, other: &Self) -> Ordering {
+        other.energy.cmp(&self.energy)
+    }
+}
+
+impl PartialOrd for ToExplore {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+/// Returns the energy it takes the given amphipod to move one space: A takes 1, B 10, C 100,
+/// and D 1000.
+fn energy(amphipod: u8) -> usize {
+    10usize.pow(amphipod as u32 - 1)
+}
+
+/// Parses a space in the burrow, which is an amphipod from A to D, or '.' when it's empty.
+fn parse_space(c: char) -> Option<u8> {
+    match c {
+        '.' => Some(EMPTY),
+        'A'..='D' => Some(c as u8 - b'A' + 1),
+        _ => None,
+    }
+}
+
+/// Returns the character for a space in the burrow.
+fn space_char(space: u8) -> char {
+    if space == EMPTY { '.' } else { (b'A' + space - 1) as char }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use super::*;
+
+    #[test]
+    fn test_sample() {
+        let burrow = Burrow::load(fs::read_to_string("input/day23_sample.txt").unwrap().as_bytes()).unwrap();
+        assert_eq!(12521, burrow.organize().unwrap());
+
+        let unfolded = burrow.unfold().unwrap();
+        assert_eq!("\
+#############
+#...........#
+###B#C#B#D###
+  #D#C#B#A#
+  #D#B#A#C#
+  #A#D#C#A#
+  #########
+", unfolded.to_string());
+        assert_eq!(44169, unfolded.organize().unwrap());
+    }
+
+    #[test]
+    fn load_invalid() {
+        match Burrow::load("#############\n#...........#\n###B#C#B#E###\n  #########\n".as_bytes()) {
+            Err(Error::Parse(e)) => assert_eq!(ParseError::new(10, "expected an amphipod or '.', found 'E'").on_line(3), e),
+            other => panic!("Expected a parse error, got {:?}", other),
+        }
+
+        let burrow = Burrow::load("#############\n#...........#\n###B#A#C#D###\n  #########\n".as_bytes()).unwrap();
+        assert_eq!(46, burrow.organize().unwrap());
+        assert!(matches!(burrow.unfold().unwrap().unfold(), Err(Error::NoSolution(_))));
+    }
+}
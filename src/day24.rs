@@ -0,0 +1,367 @@
+use std::collections::HashMap;
+use std::fmt::{Display, Formatter};
+use std::io::BufRead;
+use std::str::FromStr;
+
+use crate::error::{parse_lines, parse_num, Error, ParseError, Result};
+use crate::solution::Solution;
+
+/// Most digits that a model number can have, so it fits in a u64.
+const MAX_DIGITS: usize = 19;
+
+/// Day 24: Arithmetic Logic Unit.
+pub struct Day24;
+
+impl Solution for Day24 {
+    /// Largest and smallest model numbers that MONAD accepts.
+    type Puzzle = (u64, u64);
+    type Part1 = u64;
+    type Part2 = u64;
+
+    fn load<R: BufRead>(reader: R) -> Result<Self::Puzzle> {
+        // Both parts come out of the same search, so it's only done once.
+        Program { instructions: parse_lines(reader)? }.model_numbers()
+    }
+
+    fn part1(&(largest, _): &Self::Puzzle) -> Result<Self::Part1> {
+        Ok(largest)
+    }
+
+    fn part2(&(_, smallest): &Self::Puzzle) -> Result<Self::Part2> {
+        Ok(smallest)
+    }
+}
+
+/// Register is one of the ALU's four variables.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum Register {
+    W,
+    X,
+    Y,
+    Z,
+}
+
+impl Register {
+    fn name(&self) -> char {
+        match self {
+            Register::W => 'w',
+            Register::X => 'x',
+            Register::Y => 'y',
+            Register::Z => 'z',
+        }
+    }
+}
+
+impl FromStr for Register {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        match s {
+            "w" => Ok(Register::W),
+            "x" => Ok(Register::X),
+            "y" => Ok(Register::Y),
+            "z" => Ok(Register::Z),
+            _ => Err(ParseError::new(1, format!("expected w, x, y, or z, found '{}'", s))),
+        }
+    }
+}
+
+/// Operand is the second argument of an instruction: a register, or a number.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum Operand {
+    Register(Register),
+    Number(i64),
+}
+
+/// Instruction is a single ALU instruction.  Every instruction but `inp` stores the result of
+/// combining its register and operand back in the register.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum Instruction {
+    /// Reads the next input into the register.
+    Inp(Register),
+    Add(Register, Operand),
+    Mul(Register, Operand),
+    /// Divides, rounding toward zero.
+    Div(Register, Operand),
+    Mod(Register, Operand),
+    /// Stores 1 if the register equals the operand, or 0 if not.
+    Eql(Register, Operand),
+}
+
+impl Instruction {
+    /// Returns the register this instruction stores its result in.
+    pub fn register(&self) -> Register {
+        match *self {
+            Instruction::Inp(a) | Instruction::Add(a, _) | Instruction::Mul(a, _)
+            | Instruction::Div(a, _) | Instruction::Mod(a, _) | Instruction::Eql(a, _) => a,
+        }
+    }
+
+    /// Returns whether this instruction sets the register to a new value without reading what
+    /// was in it, like `inp x` or `mul x 0`.
+    fn overwrites(&self, register: Register) -> bool {
+        match *self {
+            Instruction::Inp(a) | Instruction::Mul(a, Operand::Number(0)) => a == register,
+            _ => false,
+        }
+    }
+
+    /// Returns whether this instruction reads or writes the register.
+    fn uses(&self, register: Register) -> bool {
+        match *self {
+            Instruction::Inp(a) => a == register,
+            Instruction::Add(a, b) | Instruction::Mul(a, b) | Instruction::Div(a, b)
+            | Instruction::Mod(a, b) | Instruction::Eql(a, b) => a == register || b == Operand::Register(register),
+        }
+    }
+}
+
+impl FromStr for Instruction {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        // Instruction looks like 'add x -1'
+        let tokens = s.split_whitespace().collect::<Vec<&str>>();
+        let register = |i: usize| tokens[i].parse::<Register>().map_err(|e| ParseError::at(s, tokens[i], e.message));
+        let operand = || match tokens[2].parse::<Register>() {
+            Ok(register) => Ok(Operand::Register(register)),
+            Err(_) => parse_num(s, tokens[2]).map(Operand::Number),
+        };
+
+        match tokens[..] {
+            ["inp", _] => Ok(Instruction::Inp(register(1)?)),
+            ["add", _, _] => Ok(Instruction::Add(register(1)?, operand()?)),
+            ["mul", _, _] => Ok(Instruction::Mul(register(1)?, operand()?)),
+            ["div", _, _] => Ok(Instruction::Div(register(1)?, operand()?)),
+            ["mod", _, _] => Ok(Instruction::Mod(register(1)?, operand()?)),
+            ["eql", _, _] => Ok(Instruction::Eql(register(1)?, operand()?)),
+            _ => Err(ParseError::new(1, format!("expected an instruction like 'add x -1', found '{}'", s))),
+        }
+    }
+}
+
+impl Display for Instruction {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let (name, a, b) = match *self {
+            Instruction::Inp(a) => return write!(f, "inp {}", a.name()),
+            Instruction::Add(a, b) => ("add", a, b),
+            Instruction::Mul(a, b) => ("mul", a, b),
+            Instruction::Div(a, b) => ("div", a, b),
+            Instruction::Mod(a, b) => ("mod", a, b),
+            Instruction::Eql(a, b) => ("eql", a, b),
+        };
+
+        match b {
+            Operand::Register(b) => write!(f, "{} {} {}", name, a.name(), b.name()),
+            Operand::Number(b) => write!(f, "{} {} {}", name, a.name(), b),
+        }
+    }
+}
+
+/// Program is a list of ALU instructions, like MONAD, the submarine's model number checker.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct Program {
+    pub instructions: Vec<Instruction>,
+}
+
+impl Program {
+    /// Runs the program with the given inputs and all registers starting at 0, and returns the
+    /// registers at the end in w, x, y, z order.  Returns an error if the program divides by
+    /// zero, takes an invalid modulus, or runs out of input.
+    pub fn run(&self, inputs: &[i64]) -> Result<[i64; 4]> {
+        execute(&self.instructions, [0; 4], inputs)
+    }
+
+    /// Returns whether MONAD accepts the given model number, which must have no zeros in it.
+    /// Valid model numbers leave 0 in z.
+    pub fn accepts(&self, model: u64) -> bool {
+        let digits = model.to_string().chars().map(|c| c.to_digit(10).unwrap() as i64).collect::<Vec<i64>>();
+
+        !digits.contains(&0) && matches!(self.run(&digits), Ok([_, _, _, 0]))
+    }
+
+    /// Returns the largest and smallest model numbers that MONAD accepts.
+    ///
+    /// MONAD reads each digit at the start of a block of instructions, and only z carries over
+    /// from one block to the next, so the search tracks the largest and smallest digits that
+    /// lead to each value of z after each block.  z acts like a stack of base-26 digits which
+    /// only shrinks when it's divided, so values of z that are at least the product of the
+    /// divisors in the remaining blocks can never get back to 0, and are pruned.
+    pub fn model_numbers(&self) -> Result<(u64, u64)> {
+        let blocks = self.blocks()?;
+        if blocks.len() > MAX_DIGITS {
+            return Err(Error::no_solution(format!("MONAD reads {} digits, but model numbers have at most {}.", blocks.len(), MAX_DIGITS)));
+        }
+
+        // limits[i] is the smallest z after block i - 1 that can't get back to 0.
+        let mut limits = vec![i64::MAX; blocks.len() + 1];
+        limits[blocks.len()] = 1;
+        for (i, block) in blocks.iter().enumerate().rev() {
+            let divisor = block.iter()
+                .filter_map(|instruction| match instruction {
+                    Instruction::Div(Register::Z, Operand::Number(n)) => Some(*n),
+                    _ => None,
+                })
+                .fold(1i64, |product, n| product.saturating_mul(n));
+
+            limits[i] = limits[i + 1].saturating_mul(divisor.max(1));
+        }
+
+        // Largest and smallest digits so far that lead to each z.
+        let mut states: HashMap<i64, (u64, u64)> = HashMap::from([(0, (0, 0))]);
+        for (i, block) in blocks.iter().enumerate() {
+            let mut next: HashMap<i64, (u64, u64)> = HashMap::new();
+
+            for (&z, &(largest, smallest)) in &states {
+                for digit in 1..=9 {
+                    // Blocks that crash aren't valid.
+                    let z = match execute(block, [0, 0, 0, z], &[digit]) {
+                        Ok([_, _, _, z]) if z < limits[i + 1] => z,
+                        _ => continue,
+                    };
+
+                    let digit = digit as u64;
+                    let (largest, smallest) = (largest * 10 + digit, smallest * 10 + digit);
+                    next.entry(z)
+                        .and_modify(|(l, s)| {
+                            *l = largest.max(*l);
+                            *s = smallest.min(*s);
+                        })
+                        .or_insert((largest, smallest));
+                }
+            }
+
+            states = next;
+        }
+
+        states.get(&0).copied()
+            .ok_or_else(|| Error::no_solution("MONAD doesn't accept any model numbers."))
+    }
+
+    /// Splits the program into blocks that each start by reading a digit into w.  Returns an
+    /// error if a block uses x or y before setting them, since then more than z would carry over
+    /// between blocks.
+    fn blocks(&self) -> Result<Vec<&[Instruction]>> {
+        let starts = self.instructions.iter().enumerate()
+            .filter(|(_, instruction)| matches!(instruction, Instruction::Inp(_)))
+            .map(|(i, _)| i)
+            .collect::<Vec<usize>>();
+
+        if starts.first() != Some(&0) {
+            return Err(Error::no_solution("MONAD should start by reading a digit."));
+        }
+
+        let mut blocks = Vec::new();
+        for (n, &start) in starts.iter().enumerate() {
+            let end = starts.get(n + 1).copied().unwrap_or(self.instructions.len());
+            let block = &self.instructions[start..end];
+
+            if block[0] != Instruction::Inp(Register::W) {
+                return Err(Error::no_solution(format!("Block {} reads a digit into {}, not w.", n + 1, block[0].register().name())));
+            }
+
+            for register in [Register::X, Register::Y] {
+                let carried = block.iter()
+                    .find(|instruction| instruction.uses(register))
+                    .is_some_and(|instruction| !instruction.overwrites(register));
+
+                if carried {
+                    return Err(Error::no_solution(format!("Block {} uses {} from the block before it.", n + 1, register.name())));
+                }
+            }
+
+            blocks.push(block);
+        }
+
+        Ok(blocks)
+    }
+}
+
+/// Runs the instructions starting with the given registers, and returns the registers at the
+/// end.
+fn execute(instructions: &[Instruction], mut registers: [i64; 4], inputs: &[i64]) -> Result<[i64; 4]> {
+    let mut inputs = inputs.iter();
+
+    for instruction in instructions {
+        let a = registers[instruction.register() as usize];
+        let value = |b: Operand| match b {
+            Operand::Register(b) => registers[b as usize],
+            Operand::Number(n) => n,
+        };
+        let overflow = |a: i64, b: i64| Error::no_solution(format!("'{}' overflows with {} and {}.", instruction, a, b));
+
+        registers[instruction.register() as usize] = match *instruction {
+            Instruction::Inp(_) => *inputs.next()
+                .ok_or_else(|| Error::no_solution("The program read more input than it was given."))?,
+            Instruction::Add(_, b) => a.checked_add(value(b)).ok_or_else(|| overflow(a, value(b)))?,
+            Instruction::Mul(_, b) => a.checked_mul(value(b)).ok_or_else(|| overflow(a, value(b)))?,
+            Instruction::Div(_, b) => match value(b) {
+                0 => return Err(Error::no_solution(format!("'{}' divides by zero.", instruction))),
+                b => a.checked_div(b).ok_or_else(|| overflow(a, b))?,
+            },
+            Instruction::Mod(_, b) => match value(b) {
+                b if a < 0 || b <= 0 => return Err(Error::no_solution(format!("'{}' takes {} mod {}.", instruction, a, b))),
+                b => a % b,
+            },
+            Instruction::Eql(_, b) => (a == value(b)) as i64,
+        };
+    }
+
+    Ok(registers)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use super::*;
+
+    fn program(s: &str) -> Program {
+        Program { instructions: parse_lines(s.as_bytes()).unwrap() }
+    }
+
+    #[test]
+    fn run_programs() {
+        assert_eq!(-7, program("inp x\nmul x -1\n").run(&[7]).unwrap()[1]);
+
+        // Stores the lowest 4 bits of the input in w, x, y, z, from highest to lowest.
+        let binary = program("inp w\nadd z w\nmod z 2\ndiv w 2\nadd y w\nmod y 2\ndiv w 2\nadd x w\nmod x 2\ndiv w 2\nmod w 2\n");
+        assert_eq!([1, 1, 0, 1], binary.run(&[13]).unwrap());
+
+        assert!(matches!(program("inp w\ndiv z w\n").run(&[0]), Err(Error::NoSolution(_))));
+        assert!(matches!(program("inp w\ninp x\n").run(&[1]), Err(Error::NoSolution(_))));
+
+        // Overflow is an error rather than a panic.
+        assert!(matches!(program("inp w\nadd w 9223372036854775807\n").run(&[1]), Err(Error::NoSolution(_))));
+        assert!(matches!(program("inp w\nmul w 9223372036854775807\n").run(&[2]), Err(Error::NoSolution(_))));
+        assert!(matches!(program("inp w\nadd x -9223372036854775807\nadd x -1\ndiv x w\n").run(&[-1]), Err(Error::NoSolution(_))));
+        assert!(matches!(program(&"inp w\n".repeat(20)).model_numbers(), Err(Error::NoSolution(_))));
+    }
+
+    #[test]
+    fn test_sample() {
+        // The sample is a MONAD-like program made up for these tests, since every player's
+        // MONAD is different.
+        let monad = program(&fs::read_to_string("input/day24_sample.txt").unwrap());
+
+        assert_eq!((29799359119499, 13157119116198), monad.model_numbers().unwrap());
+        assert!(monad.accepts(29799359119499));
+        assert!(!monad.accepts(29799359119498));
+    }
+
+    #[test]
+    fn carried_registers() {
+        assert!(matches!(program("inp w\nadd x w\ninp w\nadd z x\n").model_numbers(), Err(Error::NoSolution(_))));
+        assert!(matches!(program("inp w\nmul x 0\nadd x w\neql x 5\neql x 0\nadd z x\n").model_numbers(), Ok((5, 5))));
+    }
+
+    #[test]
+    fn parse_instructions() {
+        let instruction = "eql x -12".parse::<Instruction>().unwrap();
+        assert_eq!(Instruction::Eql(Register::X, Operand::Number(-12)), instruction);
+        assert_eq!("eql x -12", instruction.to_string());
+
+        assert_eq!(Err(ParseError::new(5, "expected w, x, y, or z, found 'q'")), "add q 1".parse::<Instruction>());
+        assert_eq!(Err(ParseError::new(1, "expected an instruction like 'add x -1', found 'jmp 3'")), "jmp 3".parse::<Instruction>());
+    }
+}
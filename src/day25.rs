@@ -1,111 +1,387 @@
-use std::fs::File;
-use std::io::{BufRead, BufReader};
+use std::fmt::{Display, Formatter};
+use std::io::BufRead;
 
-#[allow(dead_code)]
-pub fn solution() {
-    let mut map = Map::load("input/day25.txt");
+use crate::error::Result;
+use crate::grid::{Grid, Point};
+use crate::solution::{NoAnswer, Solution};
 
-    println!("Part 1: {}", map.steps());
+/// Day 25: Sea Cucumber.
+pub struct Day25;
+
+impl Solution for Day25 {
+    type Puzzle = Map;
+    type Part1 = usize;
+    type Part2 = NoAnswer;
+
+    fn load<R: BufRead>(reader: R) -> Result<Self::Puzzle> {
+        Map::load(reader)
+    }
+
+    fn part1(map: &Self::Puzzle) -> Result<Self::Part1> {
+        Ok(map.steps_with(Engine::Bitboard))
+    }
+
+    /// Day 25 only has one puzzle - the second star is awarded for finishing the other 49 days.
+    fn part2(_map: &Self::Puzzle) -> Result<Self::Part2> {
+        Ok(NoAnswer)
+    }
 }
 
+#[derive(Debug, Clone, Eq, PartialEq)]
 enum Square {
     South, East, Empty,
 }
 
 impl Square {
-    fn from_char(c: char) -> Self {
+    fn from_char(c: char) -> Option<Self> {
         match c {
-            'v' => Square::South,
-            '>' => Square::East,
-            '.' => Square::Empty,
-            _ => panic!("Invalid char: {}", c),
+            'v' => Some(Square::South),
+            '>' => Some(Square::East),
+            '.' => Some(Square::Empty),
+            _ => None,
         }
     }
 }
 
-struct Point {
-    row: usize,
-    col: usize,
+impl Display for Square {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Square::South => write!(f, "v"),
+            Square::East => write!(f, ">"),
+            Square::Empty => write!(f, "."),
+        }
+    }
+}
+
+/// Moved is the number of sea cucumbers in each herd that moved during a step.
+#[derive(Debug, Copy, Clone, Default, Eq, PartialEq)]
+pub struct Moved {
+    pub east: usize,
+    pub south: usize,
 }
 
-impl Point {
-    fn new(row: usize, col: usize) -> Self {
-        Point { row, col }
+impl Moved {
+    /// Returns the number of sea cucumbers that moved in both herds.
+    pub fn total(&self) -> usize {
+        self.east + self.south
     }
 }
 
-struct Map {
-    squares: Vec<Vec<Square>>,
+/// Engine selects how sea cucumbers are simulated.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum Engine {
+    /// Moves cucumbers one square at a time on a `Map`.
+    Grid,
+    /// Moves whole rows of cucumbers at once with a `Bitboard`.
+    Bitboard,
+}
+
+/// Map is the sea floor, with the position of each sea cucumber.  Cucumbers that move off one
+/// edge of the map reappear on the opposite edge.  Maps display in the same format they're
+/// loaded from.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct Map {
+    squares: Grid<Square>,
 }
 
 impl Map {
-    fn load(filename: &str) -> Self {
-        let f = File::open(filename).unwrap();
-        let f = BufReader::new(f);
+    /// Loads a Map from the given input, which has a character for each square.
+    pub fn load<R: BufRead>(reader: R) -> Result<Self> {
+        let squares = Grid::parse(reader, "'v', '>', or '.'", Square::from_char)?.wrapping();
+
+        Ok(Map { squares })
+    }
+
+    /// Returns the number of steps until no sea cucumbers move, including the step where
+    /// nothing moves.
+    pub fn steps(&mut self) -> usize {
+        let mut steps = 1;
+
+        while self.step().total() > 0 {
+            steps += 1;
+        }
+
+        steps
+    }
+
+    /// Returns the number of steps until no sea cucumbers move, using the given engine.  The map
+    /// is left as it is.
+    pub fn steps_with(&self, engine: Engine) -> usize {
+        match engine {
+            Engine::Grid => self.clone().steps(),
+            Engine::Bitboard => Bitboard::from(self).steps(),
+        }
+    }
 
-        let mut squares = Vec::new();
-        for line in f.lines() {
-            squares.push(line.unwrap().chars().map(|c| Square::from_char(c)).collect());
+    /// Moves the sea cucumbers once, and returns how many moved in each herd.  The east-facing
+    /// herd moves first, then the south-facing herd.  Cucumbers move into open spaces.
+    pub fn step(&mut self) -> Moved {
+        let east = self.move_herd(Square::East, 0, 1);
+        let south = self.move_herd(Square::South, 1, 0);
+
+        Moved { east, south }
+    }
+
+    /// Moves every cucumber in the given herd that has an empty square in front of it by the
+    /// given offset, all at once.  Returns the number of cucumbers that moved.
+    fn move_herd(&mut self, herd: Square, rows: isize, cols: isize) -> usize {
+        let step = |p: Point| self.squares.offset(p, rows, cols).expect("Wrapping grids have no edges");
+
+        // Check which cucumbers can move before moving any of them.
+        let moves = self.squares.iter()
+            .filter(|&(p, square)| *square == herd && self.squares[step(p)] == Square::Empty)
+            .map(|(p, _)| (p, step(p)))
+            .collect::<Vec<(Point, Point)>>();
+
+        for &(from, to) in &moves {
+            self.squares[from] = Square::Empty;
+            self.squares[to] = herd.clone();
         }
 
-        Map { squares }
+        moves.len()
     }
+}
+
+impl Display for Map {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.squares)
+    }
+}
+
+/// Bitboard is a map of the sea floor with a bitset for each row of each herd, where bit `col`
+/// of a row is set if there's a cucumber in that column.  Whole rows move at once by shifting
+/// their bits, which is much faster than moving squares on a `Map` for large maps.  Rows are
+/// stored one after another, and steps work in scratch buffers so they don't allocate.
+#[derive(Debug, Clone)]
+pub struct Bitboard {
+    cols: usize,
+    /// Number of words in each row.
+    words: usize,
+    east: Vec<u64>,
+    south: Vec<u64>,
+    /// Scratch rows for moving the east-facing herd.
+    row: Vec<u64>,
+    shifted: Vec<u64>,
+    /// Scratch rows for the south-facing cucumbers that move, which need to be found in every
+    /// row before any of them move.
+    moving: Vec<u64>,
+}
+
+impl From<&Map> for Bitboard {
+    fn from(map: &Map) -> Self {
+        let (rows, cols) = (map.squares.rows(), map.squares.cols());
+        let words = cols.div_ceil(64);
+
+        let mut bitboard = Bitboard {
+            cols,
+            words,
+            east: vec![0; rows * words],
+            south: vec![0; rows * words],
+            row: vec![0; words],
+            shifted: vec![0; words],
+            moving: vec![0; rows * words],
+        };
 
-    /// Returns the number of steps until no sea cucumbers move.
-    fn steps(&mut self) -> usize {
-        let mut steps = 0;
-        let mut moving = true;
+        for (p, square) in map.squares.iter() {
+            let herd = match square {
+                Square::East => &mut bitboard.east,
+                Square::South => &mut bitboard.south,
+                Square::Empty => continue,
+            };
+
+            herd[p.row * words + p.col / 64] |= 1 << (p.col % 64);
+        }
+
+        bitboard
+    }
+}
+
+impl Bitboard {
+    /// Returns the number of steps until no sea cucumbers move, including the step where
+    /// nothing moves.
+    pub fn steps(&mut self) -> usize {
+        let mut steps = 1;
+
+        while self.step().total() > 0 {
+            steps += 1;
+        }
+
+        steps
+    }
 
-        let rows = self.squares.len();
-        let cols = self.squares[0].len();
+    /// Moves the sea cucumbers once, and returns how many moved in each herd.
+    pub fn step(&mut self) -> Moved {
+        let (words, rows) = (self.words, self.rows());
+        let mut moved = Moved::default();
 
-        while moving {
-            // east facing herd moves, then south-facing herd.  Cucumbers move into open spaces.
+        // East-facing cucumbers move if the square to their right is empty.
+        for row in 0..rows {
+            let start = row * words;
+            let (east, south) = (&mut self.east[start..start + words], &self.south[start..start + words]);
 
-            // Check which east-facing cucumbers can move.
-            let mut move_east = Vec::new();
-            for row in 0..rows {
-                for col in 0..cols {
-                    match (&self.squares[row][col], &self.squares[row][(col + 1) % cols]) {
-                        (Square::East, Square::Empty) => move_east.push(Point::new(row, col)),
-                        (_, _) => {}
-                    }
-                }
+            // Find the empty squares, then shift them so each cucumber lines up with the square
+            // to its right.
+            for (empty, (east, south)) in self.row.iter_mut().zip(east.iter().zip(south)) {
+                *empty = !(east | south);
             }
+            mask(self.cols, &mut self.row);
+            rotate_down(self.cols, &self.row, &mut self.shifted);
 
-            // Move east-facing cucumbers.
-            for p in &move_east {
-                self.squares[p.row][p.col] = Square::Empty;
-                self.squares[p.row][(p.col + 1) % cols] = Square::East;
+            // The row now has the cucumbers that move, and shifted has where they move to.
+            for (moving, (east, empty_right)) in self.row.iter_mut().zip(east.iter().zip(&self.shifted)) {
+                *moving = east & empty_right;
             }
+            rotate_up(self.cols, &self.row, &mut self.shifted);
 
-            // Check which south-facing cucumbers can move.
-            let mut move_south = Vec::new();
-            for row in 0..rows {
-                for col in 0..cols {
-                    match (&self.squares[row][col], &self.squares[(row + 1) % rows][col]) {
-                        (Square::South, Square::Empty) => move_south.push(Point::new(row, col)),
-                        (_, _) => {}
-                    }
-                }
+            moved.east += count(&self.row);
+            for (east, (moving, moved_to)) in east.iter_mut().zip(self.row.iter().zip(&self.shifted)) {
+                *east = (*east & !moving) | moved_to;
             }
+        }
 
-            // Move south-facing cucumbers.
-            for p in &move_south {
-                self.squares[p.row][p.col] = Square::Empty;
-                self.squares[(p.row + 1) % rows][p.col] = Square::South;
+        // South-facing cucumbers move if the square below them is empty.  Every row needs to
+        // be checked before any of them move.
+        for row in 0..rows {
+            let (start, below) = (row * words, (row + 1) % rows * words);
+            for w in 0..words {
+                self.moving[start + w] = self.south[start + w] & !(self.east[below + w] | self.south[below + w]);
             }
+        }
 
-            steps += 1;
-            moving = !move_east.is_empty() || !move_south.is_empty();
+        moved.south = count(&self.moving);
+        for row in 0..rows {
+            let (start, above) = (row * words, (row + rows - 1) % rows * words);
+            for w in 0..words {
+                self.south[start + w] = (self.south[start + w] & !self.moving[start + w]) | self.moving[above + w];
+            }
         }
 
-        steps
+        moved
+    }
+
+    /// Returns the number of rows on the sea floor.
+    fn rows(&self) -> usize {
+        self.east.len() / self.words
     }
 }
 
+impl Display for Bitboard {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        for (east, south) in self.east.chunks(self.words).zip(self.south.chunks(self.words)) {
+            for col in 0..self.cols {
+                let bit = |bits: &[u64]| (bits[col / 64] >> (col % 64)) & 1 == 1;
+                let square = if bit(east) { Square::East } else if bit(south) { Square::South } else { Square::Empty };
+                write!(f, "{}", square)?;
+            }
+
+            writeln!(f)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Writes the row of `cols` bits with every bit moved to the next higher column, and the last
+/// column wrapped around to the first.
+fn rotate_up(cols: usize, bits: &[u64], rotated: &mut [u64]) {
+    let last = (bits[(cols - 1) / 64] >> ((cols - 1) % 64)) & 1;
+
+    for i in 0..bits.len() {
+        rotated[i] = (bits[i] << 1) | if i == 0 { last } else { bits[i - 1] >> 63 };
+    }
+
+    mask(cols, rotated);
+}
+
+/// Writes the row of `cols` bits with every bit moved to the next lower column, and the first
+/// column wrapped around to the last.
+fn rotate_down(cols: usize, bits: &[u64], rotated: &mut [u64]) {
+    for i in 0..bits.len() {
+        rotated[i] = (bits[i] >> 1) | bits.get(i + 1).map_or(0, |next| next << 63);
+    }
+
+    rotated[(cols - 1) / 64] |= (bits[0] & 1) << ((cols - 1) % 64);
+}
+
+/// Clears the bits past the last of `cols` columns.
+fn mask(cols: usize, bits: &mut [u64]) {
+    if !cols.is_multiple_of(64) {
+        bits[bits.len() - 1] &= (1 << (cols % 64)) - 1;
+    }
+}
+
+/// Returns the number of bits set in the row.
+fn count(bits: &[u64]) -> usize {
+    bits.iter().map(|word| word.count_ones() as usize).sum()
+}
+
 #[test]
 fn test_sample() {
-    let mut map = Map::load("input/day25_sample.txt");
+    let mut map = Map::load(std::fs::read_to_string("input/day25_sample.txt").unwrap().as_bytes()).unwrap();
     assert_eq!(58, map.steps());
 }
+
+#[test]
+fn single_steps() {
+    let mut map = Map::load("...>>>>>...\n".as_bytes()).unwrap();
+
+    assert_eq!(Moved { east: 1, south: 0 }, map.step());
+    assert_eq!("...>>>>.>..\n", map.to_string());
+    assert_eq!(Moved { east: 2, south: 0 }, map.step());
+    assert_eq!("...>>>.>.>.\n", map.to_string());
+
+    let mut map = Map::load(std::fs::read_to_string("input/day25_sample.txt").unwrap().as_bytes()).unwrap();
+    map.step();
+    assert_eq!("\
+....>.>v.>
+v.v>.>v.v.
+>v>>..>v..
+>>v>v>.>.v
+.>v.v...v.
+v>>.>vvv..
+..v...>>..
+vv...>>vv.
+>.v.v..v.v
+", map.to_string());
+}
+
+#[test]
+fn bitboard_matches_grid() {
+    let map = Map::load(std::fs::read_to_string("input/day25_sample.txt").unwrap().as_bytes()).unwrap();
+    assert_eq!(58, map.steps_with(Engine::Bitboard));
+    assert_eq!(58, map.steps_with(Engine::Grid));
+
+    // Maps that are narrower than a word, exactly a word, and wider than one but not a multiple
+    // of it, made by repeating each row of a small map.  Herds can keep moving forever, so only
+    // compare a fixed number of steps.
+    let rows = ["v>.>..v.>..", ">>..v..v.>.", ".v>..>...v.", "..v.>>v....", ">.v..v..>>v", "v..>.v.>...", ".>>v...v..>"];
+    for cols in [1, 7, 64, 130] {
+        let text = rows.iter().map(|row| row.chars().cycle().take(cols).collect::<String>() + "\n").collect::<String>();
+
+        let mut map = Map::load(text.as_bytes()).unwrap();
+        let mut bitboard = Bitboard::from(&map);
+        assert_eq!(text, bitboard.to_string());
+
+        for _ in 0..20 {
+            assert_eq!(map.step(), bitboard.step());
+            assert_eq!(map.to_string(), bitboard.to_string());
+        }
+    }
+}
+
+#[test]
+fn display_round_trip() {
+    let text = std::fs::read_to_string("input/day25_sample.txt").unwrap();
+    let map = Map::load(text.as_bytes()).unwrap();
+
+    assert_eq!(text, map.to_string());
+    assert_eq!(map, Map::load(map.to_string().as_bytes()).unwrap());
+}
+
+#[test]
+fn load_invalid() {
+    match Map::load("v>.\n.x.\n".as_bytes()) {
+        Err(crate::error::Error::Parse(e)) => assert_eq!(crate::error::ParseError::new(2, "expected 'v', '>', or '.', found 'x'").on_line(2), e),
+        _ => panic!("Expected a parse error"),
+    }
+}
@@ -1,126 +1,243 @@
-use std::fs::File;
-use std::io::{BufRead, BufReader};
+use std::cmp::Ordering;
+use std::fmt::{Display, Formatter};
+use std::io::BufRead;
 
-#[allow(dead_code)]
-pub fn solution() {
-    let nums = load("input/day3.txt");
+use crate::error::{Error, ParseError, Result};
+use crate::solution::Solution;
 
-    println!("Part 1: {}", power_consumption(&nums));
-    println!("Part 2: {}", life_support(&nums));
-}
+/// Widest reading that fits in a report.
+const MAX_WIDTH: usize = 64;
+
+/// Day 3: Binary Diagnostic.
+pub struct Day3;
+
+impl Solution for Day3 {
+    type Puzzle = Report;
+    type Part1 = u128;
+    type Part2 = u128;
 
-fn load(filename: &str) -> Vec<Vec<char>> {
-    let f = File::open(filename).unwrap();
-    let f = BufReader::new(f);
+    fn load<R: BufRead>(reader: R) -> Result<Self::Puzzle> {
+        Report::load(reader)
+    }
+
+    fn part1(report: &Self::Puzzle) -> Result<Self::Part1> {
+        power_consumption(report, Tie::Error)
+    }
 
-    f.lines()
-        .map(|line| to_char_array(&line.unwrap()))
-        .collect()
+    fn part2(report: &Self::Puzzle) -> Result<Self::Part2> {
+        life_support(report)
+    }
+}
+
+/// Report is a diagnostic report: binary readings that all have the same number of bits.
+/// Readings are packed into integers, and columns are numbered from the leftmost bit.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct Report {
+    width: usize,
+    readings: Vec<u64>,
 }
 
-/// Calculates the power consumption of the given numbers, calculated by `gamma * epsilon`.
-/// Gamma's digits are found by taking the most common bit in each position; epsilon's digits
-/// are found by taking the least.  The first bit in each number is the rightmost one.
-fn power_consumption(nums: &Vec<Vec<char>>) -> i32 {
-    // Number of ones at each index.  Gamma and epsilon have the opposite endianness of nums,
-    // but ones matches nums endianness.
-    let mut ones = vec![0; nums[0].len()];
-
-    // Count up the number of ones in each position
-    for num in nums {
-        for (i, &c) in num.iter().enumerate() {
-            if c == '1' {
-                ones[i] += 1;
+impl Report {
+    /// Loads a report from the given input, with one binary reading per line.  Every reading
+    /// must have the same number of bits, and readings can be at most 64 bits wide.  Blank lines
+    /// are skipped.
+    pub fn load<R: BufRead>(reader: R) -> Result<Self> {
+        let mut report = Report { width: 0, readings: Vec::new() };
+
+        for (i, line) in reader.lines().enumerate() {
+            let line = line?;
+            let reading = line.trim_end();
+            if reading.trim().is_empty() {
+                continue;
+            }
+
+            let error = |column: usize, message: String| ParseError::new(column, message).on_line(i + 1);
+
+            if let Some((col, c)) = reading.chars().enumerate().find(|&(_, c)| c != '0' && c != '1') {
+                return Err(error(col + 1, format!("expected a 0 or 1, found '{}'", c)).into());
+            }
+
+            if reading.len() > MAX_WIDTH {
+                return Err(error(MAX_WIDTH + 1, format!("numbers can be at most {} bits wide", MAX_WIDTH)).into());
+            }
+
+            if report.readings.is_empty() {
+                report.width = reading.len();
+            } else if report.width != reading.len() {
+                return Err(error(1, format!("expected {} bits, found {}", report.width, reading.len())).into());
             }
+
+            report.readings.push(reading.bytes().fold(0, |num, bit| num << 1 | (bit - b'0') as u64));
+        }
+
+        if report.width == 0 {
+            return Err(ParseError::new(1, "expected at least one binary number").into());
         }
+
+        Ok(report)
+    }
+
+    /// Returns the number of bits in each reading.
+    pub fn width(&self) -> usize {
+        self.width
     }
 
-    // Convert ones into gamma and epsilon.
-    // Gamma counts the most common value of each digit, and epsilon counts the least.
-    let (mut gamma, mut epsilon) = (0, 0);
-    for (i, &num_one) in ones.iter().enumerate() {
-        gamma <<= 1;
-        epsilon <<= 1;
+    /// Returns the readings in the report.
+    pub fn readings(&self) -> &[u64] {
+        &self.readings
+    }
 
-        if num_one > nums.len() / 2 {
-            // 1 is the most popular digit, so record a 1 in gamma.
-            gamma += 1;
-        } else if num_one < nums.len() / 2 {
-            // 0 is the most popular digit, so record a 1 in epsilon.
-            epsilon += 1;
-        } else {
-            panic!("Digit {} has an equal number of 0's and 1's.", i);
+    /// Returns the number of readings with a 1 in each column, counted in a single pass over
+    /// the readings.
+    pub fn ones(&self) -> Vec<usize> {
+        let mut ones = vec![0; self.width];
+
+        for &reading in &self.readings {
+            // Only visit the bits that are set.
+            let mut bits = reading;
+            while bits != 0 {
+                ones[self.width - 1 - bits.trailing_zeros() as usize] += 1;
+                bits &= bits - 1;
+            }
         }
+
+        ones
     }
 
-    gamma * epsilon
+    /// Returns the mask that selects the given column of a reading.
+    fn mask(&self, col: usize) -> u64 {
+        1 << (self.width - 1 - col)
+    }
 }
 
-/// Returns the life support rating for the given numbers, which is
-/// `oxygen generator rating * co2 scrubber rating`.  Both ratings are determined by keeping
-/// numbers that match a 'bit criteria'.  Oxygen keeps numbers with the most common value in
-/// the current bit position, and co2 keeps numbers with the least common value.
-fn life_support(nums: &Vec<Vec<char>>) -> i32 {
-    let oxygen_rating = rating(nums.clone(), |bit, common| bit == common);
-    let co2_rating = rating(nums.clone(), |bit, common| bit != common);
+/// Tie is what the gamma and epsilon rates do with a column that has as many 0's as 1's.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum Tie {
+    /// Gamma gets a 1 in the column, and epsilon gets a 0.
+    PreferOne,
+    /// Gamma gets a 0 in the column, and epsilon gets a 1.
+    PreferZero,
+    /// Ties are an error.
+    Error,
+}
+
+/// Tied is the error for a column with as many 0's as 1's, when ties are errors.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub struct Tied {
+    /// Column that's tied, starting at 1 for the leftmost bit.
+    pub column: usize,
+}
 
-    oxygen_rating * co2_rating
+impl Display for Tied {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Column {} has an equal number of 0's and 1's.", self.column)
+    }
 }
 
-/// Determines the rating for the given number, which is found by keeping numbers that match
-/// the bit criteria for each bit.
-fn rating(mut nums: Vec<Vec<char>>, keep: fn(char, char) -> bool) -> i32 {
-    let num_bits = nums[0].len();
+impl std::error::Error for Tied {}
 
-    for bit in 0..num_bits {
-        let len = nums.len();
-        if len == 1 {
-            return to_i32(&nums[0]);
-        }
+impl From<Tied> for Error {
+    fn from(e: Tied) -> Self {
+        Error::no_solution(e.to_string())
+    }
+}
 
-        // Count the number of ones at the given bit.
-        let mut num_ones = 0;
-        for num in &nums {
-            if num[bit] == '1' {
-                num_ones += 1;
-            }
-        }
+/// Returns the gamma and epsilon rates of the report.  Gamma's digits are found by taking the
+/// most common bit in each column; epsilon's digits are found by taking the least.  Columns
+/// with as many 0's as 1's are handled according to the tie policy.
+pub fn rates(report: &Report, tie: Tie) -> std::result::Result<(u64, u64), Tied> {
+    let len = report.readings.len();
+    let (mut gamma, mut epsilon) = (0u64, 0u64);
 
-        // Determine the most popular number.  Nums can have an odd or even length,
-        // and oxygen keeps 1's if there's a tie and co2 keeps 0's.
-        let common = if len % 2 == 0 && num_ones == len / 2 {
-            '1'
-        } else if num_ones > len / 2 {
-            '1'
-        } else {
-            '0'
+    for (column, num_one) in report.ones().into_iter().enumerate() {
+        // Compare against the number of zeros rather than half the length, which rounds down
+        // when there's an odd number of readings.
+        let one_is_common = match (num_one * 2).cmp(&len) {
+            Ordering::Greater => true,
+            Ordering::Less => false,
+            Ordering::Equal => match tie {
+                Tie::PreferOne => true,
+                Tie::PreferZero => false,
+                Tie::Error => return Err(Tied { column: column + 1 }),
+            },
         };
 
-        // Keep numbers that match the bit criteria.
-        nums = nums.into_iter()
-            .filter(|num| keep(num[bit], common))
-            .collect::<Vec<Vec<char>>>();
+        if one_is_common {
+            gamma |= report.mask(column);
+        } else {
+            epsilon |= report.mask(column);
+        }
     }
 
-    if nums.len() > 1 {
-        panic!("Rating not determined after considering all bits.")
+    Ok((gamma, epsilon))
+}
+
+/// Calculates the power consumption of the report, calculated by `gamma * epsilon`.  Columns
+/// with as many 0's as 1's are handled according to the tie policy.
+pub fn power_consumption(report: &Report, tie: Tie) -> Result<u128> {
+    let (gamma, epsilon) = rates(report, tie)?;
+
+    Ok(gamma as u128 * epsilon as u128)
+}
+
+/// Returns the life support rating for the report, which is
+/// `oxygen generator rating * co2 scrubber rating`.  Both ratings are determined by keeping
+/// readings that match a 'bit criteria'.  Oxygen keeps readings with the most common value in
+/// the current bit position, and co2 keeps readings with the least common value.
+pub fn life_support(report: &Report) -> Result<u128> {
+    let mut readings = report.readings.clone();
+    if let [reading] = readings[..] {
+        return Ok(reading as u128 * reading as u128);
     }
 
-    to_i32(&nums[0])
+    // Oxygen and co2 keep different values of the first bit, so after splitting the readings
+    // on it each rating can filter its own part of the same list in place.
+    let len = readings.len();
+    let ones = partition(&mut readings, report.mask(0));
+    let (with_ones, with_zeros) = readings.split_at_mut(ones);
+    let (oxygen, co2) = if ones * 2 >= len { (with_ones, with_zeros) } else { (with_zeros, with_ones) };
+
+    let oxygen_rating = rating(report, oxygen, true)?;
+    let co2_rating = rating(report, co2, false)?;
+
+    Ok(oxygen_rating as u128 * co2_rating as u128)
 }
 
-fn to_char_array(s: &str) -> Vec<char> {
-    s.chars().collect::<Vec<char>>()
+/// Determines a rating from readings that have already been filtered by the first bit.  Each
+/// remaining bit keeps the readings with the most common value if `keep_common` is true, or the
+/// least common value otherwise.  Ties count as 1 being most common.
+fn rating(report: &Report, mut readings: &mut [u64], keep_common: bool) -> Result<u64> {
+    for col in 1..report.width {
+        if readings.len() <= 1 {
+            break;
+        }
+
+        let len = readings.len();
+        let ones = partition(readings, report.mask(col));
+        let keep_ones = (ones * 2 >= len) == keep_common;
+
+        readings = if keep_ones { &mut readings[..ones] } else { &mut readings[ones..] };
+    }
+
+    match readings {
+        [reading] => Ok(*reading),
+        _ => Err(Error::no_solution("Rating not determined after considering all bits.")),
+    }
 }
 
-fn to_i32(s: &Vec<char>) -> i32 {
-    let mut num = 0;
-    for &c in s {
-        num <<= 1;
-        num += c as i32 - '0' as i32;
+/// Reorders the readings so the ones that match the mask come first, and returns how many
+/// matched.
+fn partition(readings: &mut [u64], mask: u64) -> usize {
+    let mut matched = 0;
+
+    for i in 0..readings.len() {
+        if readings[i] & mask != 0 {
+            readings.swap(i, matched);
+            matched += 1;
+        }
     }
 
-    num
+    matched
 }
 
 #[cfg(test)]
@@ -129,30 +246,85 @@ mod tests {
 
     #[test]
     fn test_power_consumption() {
-        let nums = test_nums();
-        assert_eq!(198, power_consumption(&nums));
+        let report = test_report();
+        assert_eq!(vec![7, 5, 8, 7, 5], report.ones());
+        assert_eq!(198, power_consumption(&report, Tie::Error).unwrap());
+    }
+
+    #[test]
+    fn tie_policies() {
+        // An odd number of readings can't tie, even when a column has fewer than half as 1's.
+        let odd = Report::load("110\n011\n100\n".as_bytes()).unwrap();
+        for tie in [Tie::PreferOne, Tie::PreferZero, Tie::Error] {
+            assert_eq!(Ok((0b110, 0b001)), rates(&odd, tie));
+        }
+        assert_eq!(6, power_consumption(&odd, Tie::Error).unwrap());
+
+        let even = Report::load("10\n11\n".as_bytes()).unwrap();
+        assert_eq!(Ok((0b11, 0b00)), rates(&even, Tie::PreferOne));
+        assert_eq!(Ok((0b10, 0b01)), rates(&even, Tie::PreferZero));
+        assert_eq!(Err(Tied { column: 2 }), rates(&even, Tie::Error));
+        assert_eq!(2, power_consumption(&even, Tie::PreferZero).unwrap());
+
+        match power_consumption(&even, Tie::Error) {
+            Err(Error::NoSolution(message)) => assert_eq!("Column 2 has an equal number of 0's and 1's.", message),
+            other => panic!("Expected no solution, got {:?}", other),
+        }
     }
 
     #[test]
     fn test_life_support() {
-        let nums = test_nums();
-        assert_eq!(230, life_support(&nums));
-    }
-
-    fn test_nums() -> Vec<Vec<char>> {
-        vec![
-            to_char_array("00100"),
-            to_char_array("11110"),
-            to_char_array("10110"),
-            to_char_array("10111"),
-            to_char_array("10101"),
-            to_char_array("01111"),
-            to_char_array("00111"),
-            to_char_array("11100"),
-            to_char_array("10000"),
-            to_char_array("11001"),
-            to_char_array("00010"),
-            to_char_array("01010"),
-        ]
-    }
-}
\ No newline at end of file
+        let report = test_report();
+        assert_eq!(230, life_support(&report).unwrap());
+    }
+
+    #[test]
+    fn wide_readings() {
+        let report = Report::load(format!("1{}\n1{}\n0{}\n", "0".repeat(63), "1".repeat(63), "1".repeat(63)).as_bytes()).unwrap();
+        assert_eq!(64, report.width());
+        assert_eq!(&[1 << 63, u64::MAX, u64::MAX >> 1], report.readings());
+
+        // Gamma is every bit, and epsilon is none of them.
+        assert_eq!(0, power_consumption(&report, Tie::Error).unwrap());
+        assert_eq!(u64::MAX as u128 * (u64::MAX >> 1) as u128, life_support(&report).unwrap());
+
+        assert_eq!(49, life_support(&Report::load("111\n".as_bytes()).unwrap()).unwrap());
+        assert!(matches!(life_support(&Report::load("10\n10\n".as_bytes()).unwrap()), Err(Error::NoSolution(_))));
+    }
+
+    #[test]
+    fn load_blank_lines() {
+        let report = Report::load("\n00100\n\n11110\n\n".as_bytes()).unwrap();
+        assert_eq!(5, report.width());
+        assert_eq!(&[0b00100, 0b11110], report.readings());
+
+        match Report::load("\n00100\n1111\n".as_bytes()) {
+            Err(Error::Parse(e)) => assert_eq!(ParseError::new(1, "expected 5 bits, found 4").on_line(3), e),
+            other => panic!("Expected a parse error, got {:?}", other),
+        }
+
+        assert!(matches!(Report::load("\n\n".as_bytes()), Err(Error::Parse(_))));
+    }
+
+    #[test]
+    fn test_load_invalid() {
+        match Report::load("00100\n11120\n".as_bytes()) {
+            Err(Error::Parse(e)) => assert_eq!(ParseError { line: 2, column: 4, message: "expected a 0 or 1, found '2'".to_string() }, e),
+            other => panic!("Expected a parse error, got {:?}", other),
+        }
+
+        match Report::load("00100\n1111\n".as_bytes()) {
+            Err(Error::Parse(e)) => assert_eq!(ParseError { line: 2, column: 1, message: "expected 5 bits, found 4".to_string() }, e),
+            other => panic!("Expected a parse error, got {:?}", other),
+        }
+
+        match Report::load(format!("{}\n", "0".repeat(65)).as_bytes()) {
+            Err(Error::Parse(e)) => assert_eq!(ParseError::new(65, "numbers can be at most 64 bits wide"), e),
+            other => panic!("Expected a parse error, got {:?}", other),
+        }
+    }
+
+    fn test_report() -> Report {
+        Report::load("00100\n11110\n10110\n10111\n10101\n01111\n00111\n11100\n10000\n11001\n00010\n01010\n".as_bytes()).unwrap()
+    }
+}
@@ -1,16 +1,33 @@
 use std::collections::{HashMap, HashSet, VecDeque};
-use std::fs::File;
-use std::io::{BufRead, BufReader, Lines};
-
-#[allow(dead_code)]
-pub fn solution() {
-    let mut game = BingoGame::load("input/day4.txt");
-    println!("Part 1: {}", game.first_winner());
-    println!("Part 1: {}", game.last_winner());
+use std::io::{BufRead, Lines};
+
+use crate::error::{parse_num, Error, ParseError, Result};
+use crate::solution::Solution;
+
+/// Day 4: Giant Squid.
+pub struct Day4;
+
+impl Solution for Day4 {
+    type Puzzle = BingoGame;
+    type Part1 = usize;
+    type Part2 = usize;
+
+    fn load<R: BufRead>(reader: R) -> Result<Self::Puzzle> {
+        BingoGame::load(reader)
+    }
+
+    fn part1(game: &Self::Puzzle) -> Result<Self::Part1> {
+        game.clone().first_winner()
+    }
+
+    fn part2(game: &Self::Puzzle) -> Result<Self::Part2> {
+        game.clone().last_winner()
+    }
 }
 
 /// BingoGame represents a game of bingo, and contains the numbers that are drawn and the boards.
-struct BingoGame {
+#[derive(Clone)]
+pub struct BingoGame {
     nums: VecDeque<usize>,
     boards: Vec<Board>,
     num_to_board_positions: HashMap<usize, Vec<BoardPosition>>,
@@ -18,26 +35,21 @@ struct BingoGame {
 }
 
 impl BingoGame {
-    /// Loads a game of bingo from the given file.  The first line in the game contains a
+    /// Loads a game of bingo from the given input.  The first line in the game contains a
     /// comma-separated list of numbers that are drawn, and the remaining lines are 5x5 boards
     /// separated by blank lines.
-    fn load(filename: &str) -> BingoGame {
-        let f = File::open(filename).unwrap();
-        let f = BufReader::new(f);
-        let mut lines = f.lines();
+    pub fn load<R: BufRead>(reader: R) -> Result<BingoGame> {
+        let mut lines = reader.lines();
 
         // First line is a comma-separated list of numbers.
-        let nums = lines.next().unwrap().unwrap()
-            .split(',')
-            .map(|n| n.parse::<usize>().unwrap())
-            .collect::<VecDeque<usize>>();
-
-        // Followed by an optional newline.
-        let _ = lines.next();
-
-        // Followed by the boards.
-        let boards = BingoReader::new(lines).collect::<Vec<Board>>();
+        let line = lines.next()
+            .ok_or_else(|| ParseError::new(1, "expected a line of drawn numbers"))??;
+        let nums = line.split(',')
+            .map(|n| parse_num(&line, n))
+            .collect::<std::result::Result<VecDeque<usize>, ParseError>>()?;
 
+        // Followed by the boards, which are separated by blank lines.
+        let boards = BingoReader::new(lines, 1).collect::<Result<Vec<Board>>>()?;
 
         let mut num_to_board_positions = HashMap::new();
         for (i, board) in boards.iter().enumerate() {
@@ -51,33 +63,33 @@ impl BingoGame {
             }
         }
 
-        BingoGame { nums, boards, num_to_board_positions, winners: HashSet::new() }
+        Ok(BingoGame { nums, boards, num_to_board_positions, winners: HashSet::new() })
     }
 
     /// Plays a game of bingo, returning the score of the winning board.
-    fn first_winner(&mut self) -> usize {
+    pub fn first_winner(&mut self) -> Result<usize> {
         while let Some(num) = self.nums.pop_front() {
             let winners = self.call(num);
 
             if !winners.is_empty() {
-                return winners[0]
+                return Ok(winners[0])
             }
         }
 
-        panic!("No winner after all numbers drawn.")
+        Err(Error::no_solution("No winner after all numbers drawn."))
     }
 
     /// Finishes a game of bingo, returning the score of the last winning board.
-    fn last_winner(&mut self) -> usize {
+    pub fn last_winner(&mut self) -> Result<usize> {
         while let Some(num) = self.nums.pop_front() {
             let winners = self.call(num);
 
             if !winners.is_empty() && self.winners.len() == self.boards.len() {
-                return winners[winners.len() - 1];
+                return Ok(winners[winners.len() - 1]);
             }
         }
 
-        panic!("No winner after all numbers drawn.")
+        Err(Error::no_solution("Some boards haven't won after all numbers drawn."))
     }
 
     /// Calls the given number, returning a list of scores of boards that won this round (if any).
@@ -88,10 +100,8 @@ impl BingoGame {
             let board = &mut self.boards[board_position.board];
             board.mark(board_position.row, board_position.col);
 
-            if board.is_winner() {
-                if self.winners.insert(board_position.board) {
-                    winners.push(board.score(num));
-                }
+            if board.is_winner() && self.winners.insert(board_position.board) {
+                winners.push(board.score(num));
             }
         }
 
@@ -99,43 +109,65 @@ impl BingoGame {
     }
 }
 
-
-/// `BingoReader` is an iterator that parses bingo `Board`s from a file.
-struct BingoReader {
-    lines: Lines<BufReader<File>>,
+/// `BingoReader` is an iterator that parses bingo `Board`s from lines of input.
+struct BingoReader<R: BufRead> {
+    lines: Lines<R>,
+    /// Number of the last line that was read.
+    line: usize,
 }
 
-impl BingoReader {
+impl<R: BufRead> BingoReader<R> {
     /// Constructs a new BingoReader.
-    /// Lines should be on the first bingo tile (past the drawn numbers).
-    fn new(lines: Lines<BufReader<File>>) -> BingoReader {
-        BingoReader { lines }
+    /// Lines should be past the drawn numbers, which end on the given line number.
+    fn new(lines: Lines<R>, line: usize) -> BingoReader<R> {
+        BingoReader { lines, line }
+    }
+
+    /// Reads the next board, or returns None if there aren't any boards left.
+    fn read_board(&mut self) -> Result<Option<Board>> {
+        // A board is five lines of numbers, separated by spaces.  Blank lines separate boards.
+        let mut nums = Vec::new();
+
+        while nums.len() < 5 {
+            let line = match self.lines.next() {
+                Some(line) => line?,
+                None if nums.is_empty() => return Ok(None),
+                None => return Err(ParseError::new(1, "expected 5 rows in a board")
+                    .on_line(self.line + 1).into()),
+            };
+            self.line += 1;
+
+            if nums.is_empty() && line.trim().is_empty() {
+                continue;
+            }
+
+            let row = line.split_whitespace()
+                .map(|s| parse_num(&line, s))
+                .collect::<std::result::Result<Vec<usize>, ParseError>>()
+                .map_err(|e| e.on_line(self.line))?;
+
+            if row.len() != 5 {
+                return Err(ParseError::new(1, format!("expected 5 numbers in a row, found {}", row.len()))
+                    .on_line(self.line).into());
+            }
+
+            nums.push(row);
+        }
+
+        Ok(Some(Board::new(nums)))
     }
 }
 
-impl Iterator for BingoReader {
-    type Item = Board;
+impl<R: BufRead> Iterator for BingoReader<R> {
+    type Item = Result<Board>;
 
     fn next(&mut self) -> Option<Self::Item> {
-        // A board is five lines of numbers, separated by spaces.
-        let nums = (0..5)
-            .flat_map(|_| self.lines.next())
-            .map(|line| line.unwrap().trim().split_whitespace()
-                .map(|s| s.parse::<usize>().unwrap())
-                .collect())
-            .collect::<Vec<Vec<usize>>>();
-
-        self.lines.next();
-
-        if nums.len() == 5 {
-            Some(Board::new(nums))
-        } else {
-            None
-        }
+        self.read_board().transpose()
     }
 }
 
 /// BoardPosition captures the board and position where a number lives.
+#[derive(Clone)]
 struct BoardPosition {
     board: usize,
     row: usize,
@@ -149,6 +181,7 @@ impl BoardPosition {
 }
 
 /// Board is a single bingo board.
+#[derive(Clone)]
 struct Board {
     nums: Vec<Vec<usize>>,
     marked: Vec<Vec<bool>>,
@@ -198,12 +231,24 @@ impl Board {
 
 #[cfg(test)]
 mod tests {
+    use std::fs;
+
     use super::*;
 
     #[test]
     fn play_sample() {
-        let mut game = BingoGame::load("input/day4_sample.txt");
-        assert_eq!(4512, game.first_winner());
-        assert_eq!(1924, game.last_winner());
+        let mut game = BingoGame::load(fs::read_to_string("input/day4_sample.txt").unwrap().as_bytes()).unwrap();
+        assert_eq!(4512, game.first_winner().unwrap());
+        assert_eq!(1924, game.last_winner().unwrap());
+    }
+
+    #[test]
+    fn load_invalid_board() {
+        let input = "7,4,9\n\n22 13 17 11  0\n 8  2 23  4 24\n21  9 14 16\n";
+
+        match BingoGame::load(input.as_bytes()) {
+            Err(Error::Parse(e)) => assert_eq!(ParseError { line: 5, column: 1, message: "expected 5 numbers in a row, found 4".to_string() }, e),
+            other => panic!("Expected a parse error, got {:?}", other.map(|game| game.boards.len())),
+        }
     }
 }
\ No newline at end of file
@@ -1,28 +1,38 @@
 use std::collections::HashMap;
-use std::fs::File;
-use std::io::{BufRead, BufReader};
+use std::io::BufRead;
 use std::str::FromStr;
 
-#[allow(dead_code)]
-pub fn solution() {
-    let lines = load("input/day5.txt");
+use crate::error::{parse_lines, parse_num, ParseError, Result};
+use crate::solution::Solution;
 
-    println!("Part 1: {}", num_overlapping(&lines, |line| line.is_horizontal() || line.is_vertical()));
-    println!("Part 2: {}", num_overlapping(&lines, |_| true));
-}
+/// Day 5: Hydrothermal Venture.
+pub struct Day5;
+
+impl Solution for Day5 {
+    type Puzzle = Vec<Line>;
+    type Part1 = usize;
+    type Part2 = usize;
 
-/// Loads lines from the given file.
-fn load(filename: &str) -> Vec<Line> {
-    let f = File::open(filename).unwrap();
-    let f = BufReader::new(f);
+    fn load<R: BufRead>(reader: R) -> Result<Self::Puzzle> {
+        load(reader)
+    }
 
-    f.lines()
-        .flat_map(|line| line.unwrap().parse::<Line>())
-        .collect()
+    fn part1(lines: &Self::Puzzle) -> Result<Self::Part1> {
+        Ok(num_overlapping(lines, |line| line.is_horizontal() || line.is_vertical()))
+    }
+
+    fn part2(lines: &Self::Puzzle) -> Result<Self::Part2> {
+        Ok(num_overlapping(lines, |_| true))
+    }
+}
+
+/// Loads lines from the given input.
+pub fn load<R: BufRead>(reader: R) -> Result<Vec<Line>> {
+    parse_lines(reader)
 }
 
 /// Returns the number of points where at least lines overlap.
-fn num_overlapping(lines: &Vec<Line>, keep: fn(&Line) -> bool) -> usize {
+pub fn num_overlapping(lines: &[Line], keep: fn(&Line) -> bool) -> usize {
     let mut point_lines: HashMap<Point, usize> = HashMap::new();
 
     for line in lines {
@@ -48,14 +58,16 @@ impl Point {
     }
 }
 
+/// Line is a line of hydrothermal vents.
 #[derive(Debug, Eq, PartialEq, Hash)]
-struct Line {
+pub struct Line {
     from: Point,
     to: Point,
 }
 
 impl Line {
-    fn new(x1: usize, y1: usize, x2: usize, y2: usize) -> Self {
+    /// Returns a new Line from (x1, y1) to (x2, y2).
+    pub fn new(x1: usize, y1: usize, x2: usize, y2: usize) -> Self {
         Line {
             from: Point::new(x1, y1),
             to: Point::new(x2, y2),
@@ -63,12 +75,12 @@ impl Line {
     }
 
     /// Returns whether this line is horizontal.
-    fn is_horizontal(&self) -> bool {
+    pub fn is_horizontal(&self) -> bool {
         self.from.y == self.to.y
     }
 
     /// Returns whether this line is vertical.
-    fn is_vertical(&self) -> bool {
+    pub fn is_vertical(&self) -> bool {
         self.from.x == self.to.x
     }
 
@@ -79,7 +91,7 @@ impl Line {
         let length = (self.from.x as i32 - self.to.x as i32).abs()
             .max((self.from.y as i32 - self.to.y as i32).abs());
 
-        (0..=length as i32)
+        (0..=length)
             .map(|i| Point::new(
                 (self.from.x as i32 + i * x_step) as usize,
                 (self.from.y as i32 + i * y_step) as usize,
@@ -88,21 +100,18 @@ impl Line {
     }
 }
 
-#[derive(Debug)]
-struct ParseErr {}
-
 impl FromStr for Line {
-    type Err = ParseErr;
+    type Err = ParseError;
 
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
         // Line looks like '0,9 -> 5,9'
         let nums = s.split(" -> ")
             .flat_map(|p| p.split(','))
-            .map(|n| n.parse::<usize>().unwrap())
-            .collect::<Vec<usize>>();
+            .map(|n| parse_num(s, n.trim()))
+            .collect::<std::result::Result<Vec<usize>, ParseError>>()?;
 
         if nums.len() != 4 {
-            Err(ParseErr {})
+            Err(ParseError::new(1, format!("expected a line like 'x1,y1 -> x2,y2', found '{}'", s)))
         } else {
             Ok(Line::new(nums[0], nums[1], nums[2], nums[3]))
         }
@@ -111,15 +120,24 @@ impl FromStr for Line {
 
 #[cfg(test)]
 mod tests {
+    use std::fs;
+
     use super::*;
 
     #[test]
     fn test_sample() {
-        let lines = load("input/day5_sample.txt");
+        let lines = load(fs::read_to_string("input/day5_sample.txt").unwrap().as_bytes()).unwrap();
         assert_eq!(5, num_overlapping(&lines, |line| line.is_horizontal() || line.is_vertical()));
         assert_eq!(12, num_overlapping(&lines, |_| true));
     }
 
+    #[test]
+    fn parse_line() {
+        assert_eq!(Ok(Line::new(0, 9, 5, 9)), "0,9 -> 5,9".parse());
+        assert_eq!(Err(ParseError::new(8, "expected a number, found 'five'")), "0,9 -> five,9".parse::<Line>());
+        assert_eq!(Err(ParseError::new(1, "expected a line like 'x1,y1 -> x2,y2', found '0,9 -> 5'")), "0,9 -> 5".parse::<Line>());
+    }
+
     #[test]
     fn test_line_points() {
         assert_eq!(vec![Point::new(1, 1), Point::new(2, 2), Point::new(3, 3)], Line::new(1, 1, 3, 3).points());
@@ -1,24 +1,40 @@
-use std::fs::File;
-use std::io::{BufRead, BufReader};
+use std::io::BufRead;
 
-#[allow(dead_code)]
-pub fn solution() {
-    let mut fish = Fish::load("input/day6.txt");
+use crate::error::{parse_num, ParseError, Result};
+use crate::solution::Solution;
 
-    println!("Part 1: {}", fish.tick_days(80));
-    println!("Part 2: {}", fish.tick_days(256-80));
+/// Day 6: Lanternfish.
+pub struct Day6;
+
+impl Solution for Day6 {
+    type Puzzle = Fish;
+    type Part1 = u64;
+    type Part2 = u64;
+
+    fn load<R: BufRead>(reader: R) -> Result<Self::Puzzle> {
+        Fish::load(reader)
+    }
+
+    fn part1(fish: &Self::Puzzle) -> Result<Self::Part1> {
+        Ok(fish.clone().tick_days(80))
+    }
+
+    fn part2(fish: &Self::Puzzle) -> Result<Self::Part2> {
+        Ok(fish.clone().tick_days(256))
+    }
 }
 
-#[derive(Debug)]
-struct Fish {
+/// Fish counts the lanternfish in the school by their timers.
+#[derive(Debug, Clone)]
+pub struct Fish {
     /// Indexes are timers (0..=8), and values are the number
     /// of fish at each timer.
     counts: Vec<u64>
 }
 
 impl Fish {
-    /// Constructs a new school of fish from the given timers.
-    fn new(timers: Vec<u64>) -> Self {
+    /// Constructs a new school of fish from the given timers, which range from 0 to 8.
+    pub fn new(timers: Vec<u64>) -> Self {
         // Fish ages range from 0 ..= 8
         let mut counts = vec![0; 9];
 
@@ -29,17 +45,23 @@ impl Fish {
         Self { counts }
     }
 
-    /// Loads fish from the given file, which contains a comma-separated
+    /// Loads fish from the given input, which contains a comma-separated
     /// list of fish timers on the first line.
-    fn load(filename: &str) -> Self {
-        let f = File::open(filename).unwrap();
-        let f = BufReader::new(f);
-        let ages = f.lines().next().unwrap().unwrap()
-            .split(',')
-            .map(|s| s.parse().unwrap())
-            .collect();
-
-        Fish::new(ages)
+    pub fn load<R: BufRead>(reader: R) -> Result<Self> {
+        let line = reader.lines().next()
+            .ok_or_else(|| ParseError::new(1, "expected a line of fish timers"))??;
+
+        let mut ages = Vec::new();
+        for timer in line.trim_end().split(',') {
+            let age = parse_num(&line, timer)?;
+            if age > 8 {
+                return Err(ParseError::at(&line, timer, format!("timers range from 0 to 8, found {}", age)).into());
+            }
+
+            ages.push(age);
+        }
+
+        Ok(Fish::new(ages))
     }
 
     /// Ticks this school of lanternfish.  Fish decrement their
@@ -63,7 +85,7 @@ impl Fish {
 
     /// Ticks the given number of days, returning the
     /// total number of lanternfish.
-    fn tick_days(&mut self, days: usize) -> u64 {
+    pub fn tick_days(&mut self, days: usize) -> u64 {
         for _ in 0..days {
             self.tick();
         }
@@ -72,13 +94,15 @@ impl Fish {
     }
 
     /// Returns the total number of lanternfish.
-    fn total(&self) -> u64 {
+    pub fn total(&self) -> u64 {
         self.counts.iter().sum()
     }
 }
 
 #[cfg(test)]
 mod tests {
+    use crate::error::Error;
+
     use super::*;
 
     #[test]
@@ -88,4 +112,12 @@ mod tests {
         assert_eq!(5934, fish.tick_days(80-18));
         assert_eq!(26984457539, fish.tick_days(256-80));
     }
+
+    #[test]
+    fn load_invalid_timer() {
+        match Fish::load("3,4,9,1".as_bytes()) {
+            Err(Error::Parse(e)) => assert_eq!(ParseError::new(5, "timers range from 0 to 8, found 9"), e),
+            other => panic!("Expected a parse error, got {:?}", other),
+        }
+    }
 }
@@ -1,22 +1,43 @@
-use std::fs::File;
-use std::io::{BufRead, BufReader};
+use std::io::BufRead;
 
-#[allow(dead_code)]
-pub fn solution() {
-    let crabs = load("input/day7.txt");
+use crate::error::{parse_num, Error, ParseError, Result};
+use crate::solution::Solution;
 
-    println!("Part 1: {}", align_fuel(&crabs, linear_fuel));
-    println!("Part 2: {}", align_fuel(&crabs, expensive_fuel));
+/// Day 7: The Treachery of Whales.
+pub struct Day7;
+
+impl Solution for Day7 {
+    type Puzzle = Vec<i32>;
+    type Part1 = i32;
+    type Part2 = i32;
+
+    fn load<R: BufRead>(reader: R) -> Result<Self::Puzzle> {
+        load(reader)
+    }
+
+    fn part1(crabs: &Self::Puzzle) -> Result<Self::Part1> {
+        align_fuel(crabs, linear_fuel)
+    }
+
+    fn part2(crabs: &Self::Puzzle) -> Result<Self::Part2> {
+        align_fuel(crabs, expensive_fuel)
+    }
 }
 
-fn load(filename: &str) -> Vec<i32> {
-    let f = File::open(filename).unwrap();
-    let f = BufReader::new(f);
-    f.lines().next().unwrap().unwrap().split(',').map(|n| n.parse().unwrap()).collect()
+/// Loads crab positions from the given input, which is a comma-separated list on the first line.
+pub fn load<R: BufRead>(reader: R) -> Result<Vec<i32>> {
+    let line = reader.lines().next()
+        .ok_or_else(|| ParseError::new(1, "expected a line of crab positions"))??;
+
+    let crabs = line.trim_end().split(',')
+        .map(|n| parse_num(&line, n))
+        .collect::<std::result::Result<Vec<i32>, ParseError>>()?;
+
+    Ok(crabs)
 }
 
 /// Returns the least amount of fuel required to align all of the crabs.
-fn align_fuel(crabs: &Vec<i32>, fuel: fn(i32, i32) -> i32) -> i32 {
+pub fn align_fuel(crabs: &[i32], fuel: fn(i32, i32) -> i32) -> Result<i32> {
     // Brute force - calculate the fuel at all of the positions between the crabs.
     let (min, max) = crabs.iter().fold((0, 0), |(min, max), &crab| {
         (min.min(crab), max.max(crab))
@@ -25,17 +46,17 @@ fn align_fuel(crabs: &Vec<i32>, fuel: fn(i32, i32) -> i32) -> i32 {
     (min ..= max)
         .map(|target| crabs.iter().map(|&crab| fuel(crab, target)).sum())
         .min()
-        .unwrap()
+        .ok_or_else(|| Error::no_solution("There aren't any crabs to align."))
 }
 
 /// Linear fuel calculation.  Each step costs 1 fuel.
-fn linear_fuel(crab: i32, target: i32) -> i32 {
+pub fn linear_fuel(crab: i32, target: i32) -> i32 {
     (crab - target).abs()
 }
 
 /// Expensive fuel calculation.  Each step costs one more than the last - 1 step costs 1 fuel,
 /// 2 steps costs 3 fuel, 3 costs 6, etc.
-fn expensive_fuel(crab: i32, target: i32) -> i32 {
+pub fn expensive_fuel(crab: i32, target: i32) -> i32 {
     // cost is 1 + 2 + ... + n = n*(n+1) / 2, which is the nth triangle number.
     let n = (crab - target).abs();
     n * (n + 1) / 2
@@ -43,6 +64,6 @@ fn expensive_fuel(crab: i32, target: i32) -> i32 {
 
 #[test]
 fn test_align_fuel() {
-    assert_eq!(37, align_fuel(&vec![16, 1, 2, 0, 4, 2, 7, 1, 2, 14], linear_fuel));
-    assert_eq!(168, align_fuel(&vec![16, 1, 2, 0, 4, 2, 7, 1, 2, 14], expensive_fuel));
+    assert_eq!(37, align_fuel(&[16, 1, 2, 0, 4, 2, 7, 1, 2, 14], linear_fuel).unwrap());
+    assert_eq!(168, align_fuel(&[16, 1, 2, 0, 4, 2, 7, 1, 2, 14], expensive_fuel).unwrap());
 }
\ No newline at end of file
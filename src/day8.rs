@@ -1,29 +1,40 @@
 use std::collections::{HashMap, HashSet};
 use std::fmt::{Debug, Formatter};
-use std::fs::File;
-use std::io::{BufRead, BufReader};
+use std::io::BufRead;
 use std::str::FromStr;
 use itertools::Itertools;
 
-#[allow(dead_code)]
-pub fn solution() {
-    let entries = load("input/day8.txt");
+use crate::error::{parse_lines, Error, ParseError, Result};
+use crate::solution::Solution;
 
-    println!("Part 1: {}", num_unique(&entries));
-    println!("Part 2: {}", decode(&entries));
-}
+/// Day 8: Seven Segment Search.
+pub struct Day8;
+
+impl Solution for Day8 {
+    type Puzzle = Vec<Entry>;
+    type Part1 = usize;
+    type Part2 = i32;
+
+    fn load<R: BufRead>(reader: R) -> Result<Self::Puzzle> {
+        load(reader)
+    }
 
-fn load(filename: &str) -> Vec<Entry> {
-    let f = File::open(filename).unwrap();
-    let f = BufReader::new(f);
+    fn part1(entries: &Self::Puzzle) -> Result<Self::Part1> {
+        Ok(num_unique(entries))
+    }
+
+    fn part2(entries: &Self::Puzzle) -> Result<Self::Part2> {
+        decode(entries)
+    }
+}
 
-    f.lines()
-        .flat_map(|line| line.unwrap().parse::<Entry>())
-        .collect()
+/// Loads entries from the given input, one per line.
+pub fn load<R: BufRead>(reader: R) -> Result<Vec<Entry>> {
+    parse_lines(reader)
 }
 
 /// Returns the number of times that unique digits (1, 4, 7, 8) appear in the output values.
-fn num_unique(entries: &Vec<Entry>) -> usize {
+pub fn num_unique(entries: &[Entry]) -> usize {
     // 1 has 2 segments, 4 has 4, 7 has 3, and 8 has 7.
     let segment_counts = vec![2, 3, 4, 7].into_iter().collect::<HashSet<usize>>();
 
@@ -33,22 +44,20 @@ fn num_unique(entries: &Vec<Entry>) -> usize {
 }
 
 /// Solves the wire / segment connections, and returns the sum of all of the output values.
-fn decode(entries: &Vec<Entry>) -> i32 {
+pub fn decode(entries: &[Entry]) -> Result<i32> {
     entries.iter().map(|entry| entry.solve()).sum()
 }
 
-#[derive(Debug)]
-enum ParseErr {}
-
+/// Entry is a note with the ten unique signal patterns and the four digit output value.
 #[derive(Eq, PartialEq)]
-struct Entry {
+pub struct Entry {
     signal: Vec<String>,
     output: Vec<String>,
 }
 
 impl Entry {
     /// Solves the wire / segment connection for this entry, and returns the output value.
-    fn solve(&self) -> i32 {
+    pub fn solve(&self) -> Result<i32> {
         //   a
         // b   c
         //   d
@@ -81,8 +90,7 @@ impl Entry {
         let four_letters: HashSet<char> = sorted_signals[2].chars().to_owned().collect();
 
         // length 5 - 2, 3, 5
-        for signal_index in 3 ..= 5 {
-            let signal = &sorted_signals[signal_index];
+        for signal in &sorted_signals[3 ..= 5] {
             let signal_letters: HashSet<char> = signal.chars().to_owned().collect();
 
             // 3 has all of the segments of 1
@@ -98,8 +106,7 @@ impl Entry {
         }
 
         // length 6 - 0, 6, 9
-        for signal_index in 6 ..= 8 {
-            let signal = &sorted_signals[signal_index];
+        for signal in &sorted_signals[6 ..= 8] {
             let signal_letters: HashSet<char> = signal.chars().to_owned().collect();
 
             // 6 is missing 1 segment from 1.
@@ -119,25 +126,38 @@ impl Entry {
         for s in &self.output {
             let digit = s.chars().sorted().join("");
 
-            output = output * 10 + numbers[&digit];
+            let value = numbers.get(&digit)
+                .ok_or_else(|| Error::no_solution(format!("Output '{}' doesn't match any signal in '{:?}'", s, self)))?;
+
+            output = output * 10 + value;
         }
 
-        output
+        Ok(output)
     }
 }
 
 impl FromStr for Entry {
-    type Err = ParseErr;
+    type Err = ParseError;
 
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
         // Entry looks like this: signal | output, with illuminated wires separated by spaces.
         // acedgfb cdfbe gcdfa fbcad dab cefabd cdfgeb eafb cagedb ab | cdfeb fcadb cdfeb cdbaf
+        let (signal, output) = s.split_once(" | ")
+            .ok_or_else(|| ParseError::new(1, "expected signal patterns and output separated by ' | '"))?;
+
+        if let Some(c) = s.chars().find(|c| !matches!(c, 'a'..='g' | ' ' | '|')) {
+            let column = s.find(c).unwrap() + 1;
+            return Err(ParseError::new(column, format!("expected a wire from a to g, found '{}'", c)));
+        }
 
-        let mut parts = s.split(" | ")
-            .map(|part| part.split(" ").map(|s| s.to_string()).collect::<Vec<String>>());
+        let signal = signal.split_whitespace().map(|s| s.to_string()).collect::<Vec<String>>();
+        let output = output.split_whitespace().map(|s| s.to_string()).collect::<Vec<String>>();
 
-        let signal = parts.next().unwrap();
-        let output = parts.next().unwrap();
+        // Solving relies on seeing every digit's pattern once, sorted by length.
+        let lengths = signal.iter().map(|s| s.len()).sorted().collect::<Vec<usize>>();
+        if lengths != [2, 3, 4, 5, 5, 5, 6, 6, 6, 7] {
+            return Err(ParseError::new(1, "expected signal patterns for all 10 digits"));
+        }
 
         Ok(Entry { signal, output })
     }
@@ -182,12 +202,18 @@ mod tests {
 
     #[test]
     fn test_decode() {
-        assert_eq!(61229, decode(&test_data()));
+        assert_eq!(61229, decode(&test_data()).unwrap());
+    }
+
+    #[test]
+    fn test_parse_invalid() {
+        assert_eq!(Err(ParseError::new(1, "expected signal patterns and output separated by ' | '")), "ab cd".parse::<Entry>());
+        assert_eq!(Err(ParseError::new(4, "expected a wire from a to g, found 'z'")), "ab z | cd".parse::<Entry>());
+        assert_eq!(Err(ParseError::new(1, "expected signal patterns for all 10 digits")), "ab cd | cd".parse::<Entry>());
     }
 
-    fn test_data<'a>() -> Vec<Entry> {
-        vec![
-            "be cfbegad cbdgef fgaecd cgeb fdcge agebfd fecdb fabcd edb | fdgacbe cefdb cefbgd gcbe",
+    fn test_data() -> Vec<Entry> {
+        ["be cfbegad cbdgef fgaecd cgeb fdcge agebfd fecdb fabcd edb | fdgacbe cefdb cefbgd gcbe",
             "edbfga begcd cbg gc gcadebf fbgde acbgfd abcde gfcbed gfec | fcgedb cgb dgebacf gc",
             "fgaebd cg bdaec gdafb agbcfd gdcbef bgcad gfac gcb cdgabef | cg cg fdcagb cbg",
             "fbegcd cbd adcefb dageb afcb bc aefdc ecdab fgdeca fcdbega | efabcd cedba gadfec cb",
@@ -196,8 +222,7 @@ mod tests {
             "dbcfg fgd bdegcaf fgec aegbdf ecdfab fbedc dacgb gdcebf gf | cefg dcbef fcge gbcadfe",
             "bdfegc cbegaf gecbf dfcage bdacg ed bedf ced adcbefg gebcd | ed bcgafe cdgba cbgef",
             "egadfb cdbfeg cegd fecab cgb gbdefca cg fgcdab egfdb bfceg | gbdfcae bgc cg cgb",
-            "gcafb gcf dcaebfg ecagb gf abcdeg gaef cafbge fdbac fegbdc | fgae cfgab fg bagce",
-        ].iter()
+            "gcafb gcf dcaebfg ecagb gf abcdeg gaef cafbge fdbac fegbdc | fgae cfgab fg bagce"].iter()
             .map(|s| s.parse().unwrap())
             .collect()
     }
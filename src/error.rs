@@ -0,0 +1,167 @@
+use std::fmt::{Display, Formatter};
+use std::io::{self, BufRead};
+use std::str::FromStr;
+
+/// Result of loading or solving a puzzle.
+pub type Result<T> = std::result::Result<T, Error>;
+
+/// Error describes why a puzzle couldn't be loaded or solved.
+#[derive(Debug)]
+pub enum Error {
+    /// Input couldn't be read.
+    Io(io::Error),
+    /// Input was malformed.
+    Parse(ParseError),
+    /// Input was well-formed, but the puzzle doesn't have an answer, like a game of bingo
+    /// where no board wins.
+    NoSolution(String),
+}
+
+impl Error {
+    /// Returns a new `NoSolution` error with the given explanation.
+    pub fn no_solution(message: impl Into<String>) -> Self {
+        Error::NoSolution(message.into())
+    }
+
+    /// Returns this error with any parse error moved down the given number of lines, for input
+    /// that was parsed starting part way through a file.
+    pub fn after_lines(self, lines: usize) -> Self {
+        match self {
+            Error::Parse(mut e) => {
+                e.line += lines;
+                Error::Parse(e)
+            }
+            other => other,
+        }
+    }
+}
+
+impl Display for Error {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Error::Io(e) => write!(f, "Couldn't read input: {}", e),
+            Error::Parse(e) => write!(f, "{}", e),
+            Error::NoSolution(message) => write!(f, "No solution: {}", message),
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Error::Io(e) => Some(e),
+            Error::Parse(e) => Some(e),
+            Error::NoSolution(_) => None,
+        }
+    }
+}
+
+impl From<io::Error> for Error {
+    fn from(e: io::Error) -> Self {
+        Error::Io(e)
+    }
+}
+
+impl From<ParseError> for Error {
+    fn from(e: ParseError) -> Self {
+        Error::Parse(e)
+    }
+}
+
+/// ParseError describes where and how the input was malformed.  Lines and columns start at 1.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct ParseError {
+    pub line: usize,
+    pub column: usize,
+    pub message: String,
+}
+
+impl ParseError {
+    /// Returns a new ParseError at the given column.  Errors start on line 1, since most parsers
+    /// only see a single line - use `on_line` to move the error to the line it came from.
+    pub fn new(column: usize, message: impl Into<String>) -> Self {
+        ParseError { line: 1, column, message: message.into() }
+    }
+
+    /// Returns a new ParseError at the start of the given token, which must be a slice of `line`.
+    pub fn at(line: &str, token: &str, message: impl Into<String>) -> Self {
+        ParseError::new(column_of(line, token), message)
+    }
+
+    /// Moves this error to the given line.
+    pub fn on_line(self, line: usize) -> Self {
+        ParseError { line, ..self }
+    }
+}
+
+impl Display for ParseError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "line {}, column {}: {}", self.line, self.column, self.message)
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+/// Returns the column where the given token starts.  The token must be a slice of `line`.
+pub fn column_of(line: &str, token: &str) -> usize {
+    token.as_ptr() as usize - line.as_ptr() as usize + 1
+}
+
+/// Parses the given token, which must be a slice of `line`, as a number.
+pub fn parse_num<T: FromStr>(line: &str, token: &str) -> std::result::Result<T, ParseError> {
+    token.parse()
+        .map_err(|_| ParseError::at(line, token, format!("expected a number, found '{}'", token)))
+}
+
+/// Parses every non-blank line of the reader, attaching line numbers to any errors.
+pub fn parse_lines<R, T>(reader: R) -> Result<Vec<T>>
+    where R: BufRead, T: FromStr<Err=ParseError> {
+    let mut parsed = Vec::new();
+
+    for (i, line) in reader.lines().enumerate() {
+        let line = line?;
+        if !line.trim().is_empty() {
+            parsed.push(line.parse().map_err(|e: ParseError| e.on_line(i + 1))?);
+        }
+    }
+
+    Ok(parsed)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_num() {
+        let line = "forward x5";
+        assert_eq!(Ok(7), parse_num::<i32>("7", "7"));
+        assert_eq!(Err(ParseError::new(9, "expected a number, found 'x5'")), parse_num::<i32>(line, &line[8..]));
+    }
+
+    #[test]
+    fn test_parse_lines() {
+        assert_eq!(vec![1, 2], parse_lines::<_, Num>("1\n\n2\n".as_bytes()).unwrap().into_iter().map(|n| n.0).collect::<Vec<i32>>());
+
+        match parse_lines::<_, Num>("1\n2\n  x\n".as_bytes()) {
+            Err(Error::Parse(e)) => assert_eq!(ParseError { line: 3, column: 3, message: "expected a number, found 'x'".to_string() }, e),
+            other => panic!("Expected a parse error, got {:?}", other.map(|nums| nums.len())),
+        }
+    }
+
+    #[test]
+    fn display_parse_error() {
+        assert_eq!("line 4, column 2: bad", ParseError::new(2, "bad").on_line(4).to_string());
+    }
+
+    /// Num is a number that implements FromStr with a ParseError.
+    struct Num(i32);
+
+    impl FromStr for Num {
+        type Err = ParseError;
+
+        fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+            parse_num(s, s.trim()).map(Num)
+        }
+    }
+}
@@ -0,0 +1,267 @@
+use std::fmt::{Debug, Display, Formatter};
+use std::io::BufRead;
+use std::ops::{Index, IndexMut};
+
+use crate::error::{ParseError, Result};
+
+/// Offsets to the up, down, left, and right neighbors of a point.
+const ORTHOGONAL: [(isize, isize); 4] = [(-1, 0), (1, 0), (0, -1), (0, 1)];
+
+/// Offsets to every neighbor of a point, including diagonals.
+const ALL: [(isize, isize); 8] = [(-1, -1), (-1, 0), (-1, 1), (0, -1), (0, 1), (1, -1), (1, 0), (1, 1)];
+
+/// Point is a row and column in a grid.
+#[derive(Eq, PartialEq, Hash, Copy, Clone, Ord, PartialOrd)]
+pub struct Point {
+    pub row: usize,
+    pub col: usize,
+}
+
+impl Point {
+    /// Creates a new Point at the given row and col.
+    pub fn new(row: usize, col: usize) -> Self {
+        Point { row, col }
+    }
+}
+
+impl Debug for Point {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "({}, {})", self.row, self.col)
+    }
+}
+
+/// Grid is a rectangular grid of values, like a map of the sea floor.  Grids can wrap around,
+/// so the neighbors of points on an edge include points on the opposite edge.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct Grid<T> {
+    rows: usize,
+    cols: usize,
+    /// Values in row-major order.
+    values: Vec<T>,
+    wrap: bool,
+}
+
+impl<T> Grid<T> {
+    /// Creates a new Grid with the given size, calling `f` for the value at each point.
+    pub fn from_fn(rows: usize, cols: usize, mut f: impl FnMut(Point) -> T) -> Self {
+        let values = (0..rows)
+            .flat_map(|row| (0..cols).map(move |col| Point::new(row, col)))
+            .map(&mut f)
+            .collect();
+
+        Grid { rows, cols, values, wrap: false }
+    }
+
+    /// Parses a Grid from the given input, which has a character for each value.  `f` converts
+    /// characters to values, returning None for invalid characters, and `expected` describes
+    /// the valid characters in errors.  Rows must all be the same length, and blank lines before
+    /// the first row or after the last row are ignored.
+    pub fn parse<R: BufRead>(reader: R, expected: &str, f: impl Fn(char) -> Option<T>) -> Result<Self> {
+        let mut values = Vec::new();
+        let mut rows = 0;
+        let mut cols = 0;
+
+        // Line of the first blank line, which is only an error if another row comes after it.
+        let mut blank = None;
+
+        for (i, line) in reader.lines().enumerate() {
+            let line = line?;
+            let line = line.trim_end();
+            let error = |column: usize, message: String| ParseError::new(column, message).on_line(i + 1);
+
+            if line.is_empty() {
+                if rows > 0 {
+                    blank = blank.or(Some(i + 1));
+                }
+                continue;
+            }
+
+            if let Some(blank) = blank {
+                return Err(ParseError::new(1, format!("expected {} columns, found 0", cols)).on_line(blank).into());
+            }
+
+            let start = values.len();
+            for (col, c) in line.chars().enumerate() {
+                values.push(f(c).ok_or_else(|| error(col + 1, format!("expected {}, found '{}'", expected, c)))?);
+            }
+
+            let len = values.len() - start;
+            if rows == 0 {
+                cols = len;
+            } else if len != cols {
+                return Err(error(1, format!("expected {} columns, found {}", cols, len)).into());
+            }
+
+            rows += 1;
+        }
+
+        if values.is_empty() {
+            return Err(ParseError::new(1, "expected at least one row").into());
+        }
+
+        Ok(Grid { rows, cols, values, wrap: false })
+    }
+
+    /// Returns this grid, set to wrap around at the edges.
+    pub fn wrapping(mut self) -> Self {
+        self.wrap = true;
+        self
+    }
+
+    /// Returns the number of rows in the grid.
+    pub fn rows(&self) -> usize {
+        self.rows
+    }
+
+    /// Returns the number of columns in the grid.
+    pub fn cols(&self) -> usize {
+        self.cols
+    }
+
+    /// Returns the number of values in the grid.
+    pub fn len(&self) -> usize {
+        self.values.len()
+    }
+
+    /// Returns whether the grid has no values.
+    pub fn is_empty(&self) -> bool {
+        self.values.is_empty()
+    }
+
+    /// Returns the value at the given point, or None if it's outside the grid.
+    pub fn get(&self, p: Point) -> Option<&T> {
+        (p.row < self.rows && p.col < self.cols).then(|| &self.values[p.row * self.cols + p.col])
+    }
+
+    /// Returns every point in the grid, row by row.
+    pub fn points(&self) -> impl Iterator<Item=Point> {
+        let cols = self.cols;
+        (0..self.rows).flat_map(move |row| (0..cols).map(move |col| Point::new(row, col)))
+    }
+
+    /// Returns every point in the grid with its value, row by row.
+    pub fn iter(&self) -> impl Iterator<Item=(Point, &T)> {
+        self.points().zip(self.values.iter())
+    }
+
+    /// Returns the point the given number of rows and columns away from `p`, or None if it's off
+    /// the grid.  Points on wrapping grids are never off the grid.
+    pub fn offset(&self, p: Point, rows: isize, cols: isize) -> Option<Point> {
+        let step = |at: usize, by: isize, len: usize| {
+            if self.wrap {
+                // An empty axis doesn't have any points to wrap around to.
+                (len > 0).then(|| (at as isize + by).rem_euclid(len as isize) as usize)
+            } else {
+                at.checked_add_signed(by).filter(|&to| to < len)
+            }
+        };
+
+        Some(Point::new(step(p.row, rows, self.rows)?, step(p.col, cols, self.cols)?))
+    }
+
+    /// Returns the up, down, left, and right neighbors of the given point.
+    pub fn neighbors4(&self, p: Point) -> impl Iterator<Item=Point> + '_ {
+        ORTHOGONAL.into_iter().filter_map(move |(rows, cols)| self.offset(p, rows, cols))
+    }
+
+    /// Returns every neighbor of the given point, including diagonals.
+    pub fn neighbors8(&self, p: Point) -> impl Iterator<Item=Point> + '_ {
+        ALL.into_iter().filter_map(move |(rows, cols)| self.offset(p, rows, cols))
+    }
+}
+
+impl Grid<i32> {
+    /// Parses a Grid from the given input, which has a digit for each value.
+    pub fn digits<R: BufRead>(reader: R) -> Result<Self> {
+        Grid::parse(reader, "a digit", |c| c.to_digit(10).map(|d| d as i32))
+    }
+}
+
+impl<T> Index<Point> for Grid<T> {
+    type Output = T;
+
+    fn index(&self, p: Point) -> &T {
+        assert!(p.row < self.rows && p.col < self.cols, "{:?} is outside the {}x{} grid", p, self.rows, self.cols);
+        &self.values[p.row * self.cols + p.col]
+    }
+}
+
+impl<T> IndexMut<Point> for Grid<T> {
+    fn index_mut(&mut self, p: Point) -> &mut T {
+        assert!(p.row < self.rows && p.col < self.cols, "{:?} is outside the {}x{} grid", p, self.rows, self.cols);
+        &mut self.values[p.row * self.cols + p.col]
+    }
+}
+
+impl<T: Display> Display for Grid<T> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        for row in 0..self.rows {
+            for value in &self.values[row * self.cols..(row + 1) * self.cols] {
+                write!(f, "{}", value)?;
+            }
+
+            writeln!(f)?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error::Error;
+
+    #[test]
+    fn parse_digits() {
+        let grid = Grid::digits("12\n34\n".as_bytes()).unwrap();
+        assert_eq!((2, 2), (grid.rows(), grid.cols()));
+        assert_eq!(3, grid[Point::new(1, 0)]);
+        assert_eq!(None, grid.get(Point::new(2, 0)));
+        assert_eq!("12\n34\n", grid.to_string());
+
+        match Grid::digits("12\n3\n".as_bytes()) {
+            Err(Error::Parse(e)) => assert_eq!(ParseError::new(1, "expected 2 columns, found 1").on_line(2), e),
+            other => panic!("Expected a parse error, got {:?}", other),
+        }
+
+        match Grid::digits("12\n3x\n".as_bytes()) {
+            Err(Error::Parse(e)) => assert_eq!(ParseError::new(2, "expected a digit, found 'x'").on_line(2), e),
+            other => panic!("Expected a parse error, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn parse_blank_lines() {
+        let grid = Grid::digits("\n12\n34\n\n  \n".as_bytes()).unwrap();
+        assert_eq!("12\n34\n", grid.to_string());
+
+        match Grid::digits("12\n\n\n34\n".as_bytes()) {
+            Err(Error::Parse(e)) => assert_eq!(ParseError::new(1, "expected 2 columns, found 0").on_line(2), e),
+            other => panic!("Expected a parse error, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn empty_axis() {
+        let grid = Grid::from_fn(2, 0, |_| 0);
+        assert_eq!("\n\n", grid.to_string());
+        assert_eq!(None, grid.wrapping().offset(Point::new(1, 0), 0, 1));
+        assert_eq!("", Grid::from_fn(0, 3, |_| 0).to_string());
+    }
+
+    #[test]
+    fn neighbors() {
+        // Each value is the index of its point, so neighbors are easy to read.
+        let grid = Grid::from_fn(3, 4, |p| p.row * 4 + p.col);
+        let values = |grid: &Grid<usize>, points: Vec<Point>| points.iter().map(|&p| grid[p]).collect::<Vec<usize>>();
+
+        assert_eq!(vec![4, 1], values(&grid, grid.neighbors4(Point::new(0, 0)).collect()));
+        assert_eq!(vec![1, 4, 5], values(&grid, grid.neighbors8(Point::new(0, 0)).collect()));
+        assert_eq!(vec![0, 1, 2, 4, 6, 8, 9, 10], values(&grid, grid.neighbors8(Point::new(1, 1)).collect()));
+
+        let grid = grid.wrapping();
+        assert_eq!(vec![8, 4, 3, 1], values(&grid, grid.neighbors4(Point::new(0, 0)).collect()));
+        assert_eq!(Some(Point::new(2, 0)), grid.offset(Point::new(2, 3), 0, 1));
+    }
+}
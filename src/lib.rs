@@ -0,0 +1,57 @@
+//! Solutions to the [2021 advent of code](https://adventofcode.com/2021/).
+//!
+//! Each day lives in its own module, like `day15`, and implements [`Solution`]: input is loaded
+//! into a typed puzzle once, and both parts are solved from that puzzle.  The types and functions
+//! that do the work, like day 15's A* search or day 18's snailfish arithmetic, are public too.
+//!
+//! ```
+//! use adventofcode_2021::day1::Day1;
+//! use adventofcode_2021::Solution;
+//!
+//! let depths = Day1::parse("199\n200\n208\n210\n200\n207\n240\n269\n260\n263\n")?;
+//!
+//! assert_eq!(7, Day1::part1(&depths)?);
+//! assert_eq!(5, Day1::part2(&depths)?);
+//! # Ok::<(), adventofcode_2021::Error>(())
+//! ```
+//!
+//! The `runner` module solves, benchmarks, or verifies a day by number, and is what the command
+//! line uses.
+
+pub mod bench;
+pub mod error;
+pub mod grid;
+pub mod json;
+pub mod runner;
+pub mod solution;
+pub mod verify;
+
+pub mod day1;
+pub mod day2;
+pub mod day3;
+pub mod day4;
+pub mod day5;
+pub mod day6;
+pub mod day7;
+pub mod day8;
+pub mod day9;
+pub mod day10;
+pub mod day11;
+pub mod day12;
+pub mod day13;
+pub mod day14;
+pub mod day15;
+pub mod day16;
+pub mod day17;
+pub mod day18;
+pub mod day19;
+pub mod day20;
+pub mod day21;
+pub mod day22;
+pub mod day23;
+pub mod day24;
+pub mod day25;
+
+pub use error::{Error, ParseError, Result};
+pub use runner::{Format, Part};
+pub use solution::{NoAnswer, Solution};
@@ -1,24 +1,34 @@
-mod day1;
-mod day2;
-mod day3;
-mod day4;
-mod day5;
-mod day6;
-mod day7;
-mod day8;
-mod day9;
-mod day10;
-mod day11;
-mod day12;
-mod day13;
-mod day14;
-mod day15;
-mod day16;
-mod day17;
-mod day18;
-mod day21;
-mod day25;
+use std::env;
+use std::process;
+
+use adventofcode_2021::runner;
+
+use crate::cli::{Command, USAGE};
+
+mod cli;
 
 fn main() {
-    day18::solution();
+    let args = env::args().skip(1).collect::<Vec<String>>();
+
+    let result = match Command::parse(&args) {
+        Ok(Command::Run { day, part, input, format }) => runner::run(day, part, &input, format),
+        Ok(Command::Bench { days, runs, output, baseline }) =>
+            runner::bench(&days, runs, output.as_deref(), baseline.as_deref()),
+        Ok(Command::All { format }) => runner::all(format),
+        Ok(Command::Verify { days, answers }) => runner::verify(&answers, &days),
+        Ok(Command::Odds { csv }) => runner::odds(csv),
+        Ok(Command::Help) => {
+            println!("{}", USAGE);
+            Ok(())
+        }
+        Err(message) => {
+            eprintln!("{}\n\n{}", message, USAGE);
+            process::exit(2);
+        }
+    };
+
+    if let Err(message) = result {
+        eprintln!("{}", message);
+        process::exit(1);
+    }
 }
@@ -0,0 +1,515 @@
+use std::fmt::{Display, Write};
+use std::fs::{self, File};
+use std::io::{self, BufRead, BufReader};
+use std::path::Path;
+use std::panic::{self, AssertUnwindSafe};
+use std::time::{Duration, Instant};
+
+use rayon::prelude::*;
+
+use crate::bench::{self, Baseline, Timings};
+use crate::error;
+use crate::json::Json;
+use crate::solution::Solution;
+use crate::verify::{self, Manifest};
+use crate::{day1, day2, day3, day4, day5, day6, day7, day8, day9, day10, day11, day12, day13, day14,
+            day15, day16, day17, day18, day19, day20, day21, day22, day23, day24, day25};
+
+/// Days that have solutions.
+pub const DAYS: &[u32] = &[1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 16, 17, 18, 19, 20, 21, 22, 23, 24, 25];
+
+/// Part selects which parts of a day's puzzle to solve.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum Part {
+    One,
+    Two,
+    Both,
+}
+
+impl Part {
+    /// Returns whether part 1 should be solved.
+    pub fn includes_one(&self) -> bool {
+        matches!(self, Part::One | Part::Both)
+    }
+
+    /// Returns whether part 2 should be solved.
+    pub fn includes_two(&self) -> bool {
+        matches!(self, Part::Two | Part::Both)
+    }
+}
+
+/// `DayFn` is a function that works with any day's `Solution`.  `with_day` calls it with the
+/// solution for a day number, so the day's puzzle and answer types stay statically typed.
+pub trait DayFn {
+    type Output;
+
+    fn call<S: Solution>(self) -> Self::Output;
+}
+
+/// Calls the given function with the solution for the given day.  Returns an error if the day
+/// doesn't exist or hasn't been implemented.
+pub fn with_day<F: DayFn>(day: u32, f: F) -> Result<F::Output, String> {
+    match day {
+        1 => Ok(f.call::<day1::Day1>()),
+        2 => Ok(f.call::<day2::Day2>()),
+        3 => Ok(f.call::<day3::Day3>()),
+        4 => Ok(f.call::<day4::Day4>()),
+        5 => Ok(f.call::<day5::Day5>()),
+        6 => Ok(f.call::<day6::Day6>()),
+        7 => Ok(f.call::<day7::Day7>()),
+        8 => Ok(f.call::<day8::Day8>()),
+        9 => Ok(f.call::<day9::Day9>()),
+        10 => Ok(f.call::<day10::Day10>()),
+        11 => Ok(f.call::<day11::Day11>()),
+        12 => Ok(f.call::<day12::Day12>()),
+        13 => Ok(f.call::<day13::Day13>()),
+        14 => Ok(f.call::<day14::Day14>()),
+        15 => Ok(f.call::<day15::Day15>()),
+        16 => Ok(f.call::<day16::Day16>()),
+        17 => Ok(f.call::<day17::Day17>()),
+        18 => Ok(f.call::<day18::Day18>()),
+        19 => Ok(f.call::<day19::Day19>()),
+        20 => Ok(f.call::<day20::Day20>()),
+        21 => Ok(f.call::<day21::Day21>()),
+        22 => Ok(f.call::<day22::Day22>()),
+        23 => Ok(f.call::<day23::Day23>()),
+        24 => Ok(f.call::<day24::Day24>()),
+        25 => Ok(f.call::<day25::Day25>()),
+        _ if (1..=25).contains(&day) => Err(format!("Day {} is not implemented yet.", day)),
+        _ => Err(format!("There is no day {} - days run from 1 to 25.", day)),
+    }
+}
+
+/// Format selects how the runner prints its results.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum Format {
+    /// Text for people to read.
+    Text,
+    /// JSON for scripts, with one record per day on each line.
+    Json,
+}
+
+/// Answers holds the answer to each part of a puzzle as text, or the error that kept a part from
+/// being solved.  Parts that weren't asked for are None.
+#[derive(Debug, Clone, Default, Eq, PartialEq)]
+pub struct Answers {
+    pub part1: Option<Result<String, String>>,
+    pub part2: Option<Result<String, String>>,
+}
+
+/// Report is the outcome of solving a day's puzzle: the answers, or why the puzzle couldn't be
+/// loaded, and how long it took.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct Report {
+    pub day: u32,
+    pub answers: Result<Answers, String>,
+    pub elapsed: Duration,
+}
+
+impl Report {
+    /// Returns whether the puzzle was loaded, and every part that was asked for was solved.
+    pub fn is_ok(&self) -> bool {
+        match &self.answers {
+            Ok(answers) => [&answers.part1, &answers.part2].iter().all(|answer| !matches!(answer, Some(Err(_)))),
+            Err(_) => false,
+        }
+    }
+
+    /// Returns this report as a JSON record, like
+    /// `{"day":1,"ok":true,"elapsed_ns":12000,"part1":{"answer":"7"},"part2":null,"error":null}`.
+    /// Parts that weren't asked for are null, and parts that failed have an `error` instead of
+    /// an `answer`.  If the puzzle couldn't be loaded, both parts are null and `error` says why.
+    pub fn to_json(&self) -> Json {
+        let part = |answer: &Option<Result<String, String>>| match answer {
+            Some(Ok(answer)) => Json::Object(vec![("answer", answer.as_str().into())]),
+            Some(Err(error)) => Json::Object(vec![("error", error.as_str().into())]),
+            None => Json::Null,
+        };
+
+        let (part1, part2, error) = match &self.answers {
+            Ok(answers) => (part(&answers.part1), part(&answers.part2), Json::Null),
+            Err(error) => (Json::Null, Json::Null, error.as_str().into()),
+        };
+
+        Json::Object(vec![
+            ("day", self.day.into()),
+            ("ok", self.is_ok().into()),
+            ("elapsed_ns", (self.elapsed.as_nanos() as u64).into()),
+            ("part1", part1),
+            ("part2", part2),
+            ("error", error),
+        ])
+    }
+}
+
+/// Returns the default input file for the given day, like `input/day15.txt`.
+pub fn default_input(day: u32) -> String {
+    format!("input/day{}.txt", day)
+}
+
+/// Solves the given parts of a day's puzzle using the input in the given file, printing the
+/// answers in the given format.  A filename of `-` reads the input from stdin.  Returns an error
+/// if the day hasn't been implemented or the puzzle can't be solved.
+pub fn run(day: u32, part: Part, filename: &str, format: Format) -> Result<(), String> {
+    match format {
+        Format::Text => with_day(day, Solve { filename, part })?
+            .map_err(|e| format!("Day {}: {}", day, e)),
+        Format::Json => {
+            let report = report(day, part, filename);
+            println!("{}", report.to_json());
+
+            match report.is_ok() {
+                true => Ok(()),
+                false => Err(format!("Day {} failed.", day)),
+            }
+        }
+    }
+}
+
+/// Solves the given parts of a day's puzzle using the input in the given file.  Returns an error
+/// if the day hasn't been implemented or the puzzle can't be loaded.
+pub fn answers(day: u32, part: Part, filename: &str) -> Result<Answers, String> {
+    with_day(day, SolveParts { filename, part })?
+        .map_err(|e| format!("Day {}: {}", day, e))
+}
+
+/// Solves the given parts of a day's puzzle like `answers`, and times it.  Panics while solving
+/// are caught and reported as errors, so one broken day can't take down others.
+pub fn report(day: u32, part: Part, filename: &str) -> Report {
+    let start = Instant::now();
+    let answers = panic::catch_unwind(AssertUnwindSafe(|| answers(day, part, filename)))
+        .unwrap_or_else(|payload| Err(format!("Day {} panicked: {}", day, panic_message(payload.as_ref()))));
+
+    Report { day, answers, elapsed: start.elapsed() }
+}
+
+/// Returns the given days split into the ones whose input file exists, and the ones whose input
+/// file is missing.  `input` returns the input file for a day, like `default_input`.
+pub fn split_missing(days: &[u32], input: impl Fn(u32) -> String) -> (Vec<u32>, Vec<u32>) {
+    days.iter().partition(|&&day| Path::new(&input(day)).is_file())
+}
+
+/// Solves every implemented day in parallel using their default inputs, and prints a table of
+/// answers and timings, or a JSON record per day.  Days without a default input are skipped.
+/// Returns an error if any day failed.
+pub fn all(format: Format) -> Result<(), String> {
+    let (days, skipped) = split_missing(DAYS, default_input);
+
+    // Panics are reported in the table, so don't print them as they happen.
+    let hook = panic::take_hook();
+    panic::set_hook(Box::new(|_| {}));
+
+    let reports = days.par_iter()
+        .map(|&day| report(day, Part::Both, &default_input(day)))
+        .collect::<Vec<Report>>();
+
+    panic::set_hook(hook);
+
+    match format {
+        Format::Text => print!("{}{}", summary(&reports), skipped_note(&skipped)),
+        Format::Json => {
+            reports.iter().for_each(|report| println!("{}", report.to_json()));
+            skipped.iter().for_each(|&day| println!("{}", skipped_json(day)));
+        }
+    }
+
+    match reports.iter().filter(|report| !report.is_ok()).count() {
+        0 => Ok(()),
+        failures => Err(format!("{} of {} days failed.", failures, reports.len())),
+    }
+}
+
+/// Checks the answers recorded in the given manifest file for the given days, or every day in
+/// the manifest if no days are given, and prints a report.  Returns an error if any answer
+/// couldn't be checked or didn't match.
+pub fn verify(manifest: &str, days: &[u32]) -> Result<(), String> {
+    let manifest = Manifest::parse(&read_input(manifest)?)
+        .map_err(|e| format!("Couldn't read answers '{}': {}", manifest, e))?;
+
+    let checks = verify::check(&manifest, days, answers);
+    print!("{}", verify::report(&checks));
+
+    match checks.iter().filter(|(_, outcome)| *outcome != verify::Outcome::Pass).count() {
+        0 => Ok(()),
+        failures => Err(format!("{} of {} answers didn't pass.", failures, checks.len())),
+    }
+}
+
+/// Benchmarks the given days using their default inputs, printing a table of timings.  Days
+/// without a default input are skipped.  Each day is parsed and solved `runs` times.  Timings
+/// are written to the output file as CSV if one is given, and compared against a baseline CSV
+/// file from an earlier run if one is given.
+pub fn bench(days: &[u32], runs: usize, output: Option<&str>, baseline: Option<&str>) -> Result<(), String> {
+    let baseline = baseline
+        .map(|filename| read_input(filename).and_then(|text| Baseline::parse(&text)
+            .map_err(|e| format!("Couldn't read baseline '{}': {}", filename, e))))
+        .transpose()?;
+
+    let (days, skipped) = split_missing(days, default_input);
+
+    let mut results: Vec<(u32, Timings)> = Vec::new();
+    for &day in &days {
+        let input = read_input(&default_input(day))?;
+        let timings = with_day(day, Bench { input: &input, runs })?
+            .map_err(|e| format!("Day {}: {}", day, e))?;
+
+        results.push((day, timings));
+    }
+
+    print!("{}{}", bench::table(&results, baseline.as_ref()), skipped_note(&skipped));
+
+    if let Some(filename) = output {
+        fs::write(filename, bench::to_csv(&results))
+            .map_err(|e| format!("Couldn't write timings to '{}': {}", filename, e))?;
+    }
+
+    Ok(())
+}
+
+/// Prints the chance of each player winning a game of dirac dice from every pair of starting
+/// positions, as a matrix, or as CSV if `csv` is set.
+pub fn odds(csv: bool) -> Result<(), String> {
+    let table = day21::odds_table(&day21::Rules::DIRAC).map_err(|e| e.to_string())?;
+
+    match csv {
+        true => print!("{}", day21::odds_csv(&table)),
+        false => print!("{}", day21::odds_matrix(&table).map_err(|e| e.to_string())?),
+    }
+
+    Ok(())
+}
+
+/// Opens the given input file for reading, or stdin if the filename is `-`.
+fn open_input(filename: &str) -> Result<Box<dyn BufRead>, String> {
+    if filename == "-" {
+        return Ok(Box::new(io::stdin().lock()));
+    }
+
+    let f = File::open(filename)
+        .map_err(|e| format!("Couldn't open input file '{}': {}", filename, e))?;
+
+    Ok(Box::new(BufReader::new(f)))
+}
+
+/// Reads the whole input file into memory.
+fn read_input(filename: &str) -> Result<String, String> {
+    fs::read_to_string(filename)
+        .map_err(|e| format!("Couldn't read input file '{}': {}", filename, e))
+}
+
+/// `Solve` loads a puzzle from a file, and prints the answers to the given parts.
+struct Solve<'a> {
+    filename: &'a str,
+    part: Part,
+}
+
+impl DayFn for Solve<'_> {
+    type Output = Result<(), String>;
+
+    fn call<S: Solution>(self) -> Self::Output {
+        let puzzle = S::load(open_input(self.filename)?)
+            .map_err(|e| e.to_string())?;
+
+        if self.part.includes_one() {
+            print_answer(1, S::part1(&puzzle).map_err(|e| e.to_string())?);
+        }
+
+        if self.part.includes_two() {
+            print_answer(2, S::part2(&puzzle).map_err(|e| e.to_string())?);
+        }
+
+        Ok(())
+    }
+}
+
+/// `SolveParts` loads a puzzle from a file, and returns the answers to the given parts.
+struct SolveParts<'a> {
+    filename: &'a str,
+    part: Part,
+}
+
+impl DayFn for SolveParts<'_> {
+    type Output = Result<Answers, String>;
+
+    fn call<S: Solution>(self) -> Self::Output {
+        let puzzle = S::load(open_input(self.filename)?)
+            .map_err(|e| e.to_string())?;
+
+        let solve = |answer: error::Result<String>| answer.map_err(|e| e.to_string());
+
+        Ok(Answers {
+            part1: self.part.includes_one().then(|| solve(S::part1(&puzzle).map(|answer| answer.to_string()))),
+            part2: self.part.includes_two().then(|| solve(S::part2(&puzzle).map(|answer| answer.to_string()))),
+        })
+    }
+}
+
+/// `Bench` times parsing and solving a puzzle.
+struct Bench<'a> {
+    input: &'a str,
+    runs: usize,
+}
+
+impl DayFn for Bench<'_> {
+    type Output = error::Result<Timings>;
+
+    fn call<S: Solution>(self) -> Self::Output {
+        bench::measure::<S>(self.input, self.runs)
+    }
+}
+
+/// Returns the message a panic was started with.
+fn panic_message(payload: &(dyn std::any::Any + Send)) -> String {
+    if let Some(message) = payload.downcast_ref::<&str>() {
+        message.to_string()
+    } else if let Some(message) = payload.downcast_ref::<String>() {
+        message.clone()
+    } else {
+        "unknown panic".to_string()
+    }
+}
+
+/// Returns a table with a row of answers and timing for each report.  Answers that span
+/// multiple lines, and errors, are too big for the table and are listed after it.
+fn summary(reports: &[Report]) -> String {
+    let mut rows = vec![["Day".to_string(), "Part 1".to_string(), "Part 2".to_string(), "Time".to_string()]];
+    let mut notes = Vec::new();
+
+    for report in reports {
+        let mut cell = |part: u8, answer: &Option<Result<String, String>>| match answer {
+            Some(Ok(answer)) if answer.contains('\n') => {
+                notes.push(format!("Day {} part {}:\n{}", report.day, part, answer.trim_end()));
+                "see below".to_string()
+            }
+            Some(Ok(answer)) => answer.clone(),
+            Some(Err(error)) => {
+                notes.push(format!("Day {} part {} failed: {}", report.day, part, error));
+                "error".to_string()
+            }
+            None => "-".to_string(),
+        };
+
+        let (part1, part2) = match &report.answers {
+            Ok(answers) => (cell(1, &answers.part1), cell(2, &answers.part2)),
+            Err(error) => {
+                notes.push(error.clone());
+                ("error".to_string(), "error".to_string())
+            }
+        };
+
+        rows.push([report.day.to_string(), part1, part2, bench::format_duration(report.elapsed)]);
+    }
+
+    let widths = (0..4)
+        .map(|col| rows.iter().map(|row| row[col].chars().count()).max().unwrap())
+        .collect::<Vec<usize>>();
+
+    let mut table = String::new();
+    for row in &rows {
+        writeln!(table, "{:>w0$}  {:<w1$}  {:<w2$}  {:>w3$}", row[0], row[1], row[2], row[3],
+                 w0 = widths[0], w1 = widths[1], w2 = widths[2], w3 = widths[3]).unwrap();
+    }
+
+    for note in notes {
+        write!(table, "\n{}\n", note).unwrap();
+    }
+
+    table
+}
+
+/// Returns a note listing the days that were skipped because they don't have an input file, or
+/// nothing if no days were skipped.
+fn skipped_note(skipped: &[u32]) -> String {
+    match skipped {
+        [] => String::new(),
+        _ => format!("\nSkipped days without an input file: {}\n",
+                     skipped.iter().map(|day| day.to_string()).collect::<Vec<String>>().join(", ")),
+    }
+}
+
+/// Returns the JSON record for a day that was skipped because it doesn't have an input file.
+/// It has the same fields as every other record, with an `error` saying which input is missing.
+fn skipped_json(day: u32) -> Json {
+    let error = format!("no default input '{}'", default_input(day));
+    Report { day, answers: Err(error), elapsed: Duration::ZERO }.to_json()
+}
+
+/// Prints the answer to a part.  Answers that span multiple lines, like day 13's letters,
+/// start on their own line.
+fn print_answer(part: u8, answer: impl Display) {
+    let answer = answer.to_string();
+
+    if answer.contains('\n') {
+        println!("Part {}:\n{}", part, answer);
+    } else {
+        println!("Part {}: {}", part, answer);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn summary_table() {
+        let answers = |part1: Result<&str, &str>, part2: Option<Result<&str, &str>>| Answers {
+            part1: Some(part1.map(|s| s.to_string()).map_err(|s| s.to_string())),
+            part2: part2.map(|answer| answer.map(|s| s.to_string()).map_err(|s| s.to_string())),
+        };
+
+        let reports = vec![
+            Report { day: 1, answers: Ok(answers(Ok("7"), Some(Ok("5")))), elapsed: Duration::from_micros(12) },
+            Report { day: 13, answers: Ok(answers(Ok("17"), Some(Ok("#\n#\n")))), elapsed: Duration::from_millis(3) },
+            Report { day: 21, answers: Ok(answers(Ok("739785"), Some(Err("No solution")))), elapsed: Duration::from_nanos(15) },
+            Report { day: 25, answers: Err("Day 25: line 1, column 1: bad".to_string()), elapsed: Duration::from_nanos(9) },
+        ];
+
+        assert!(reports[0].is_ok() && reports[1].is_ok());
+        assert!(!reports[2].is_ok() && !reports[3].is_ok());
+        assert_eq!("\
+Day  Part 1  Part 2       Time
+  1  7       5          12.0µs
+ 13  17      see below   3.0ms
+ 21  739785  error        15ns
+ 25  error   error         9ns
+
+Day 13 part 2:
+#
+#
+
+Day 21 part 2 failed: No solution
+
+Day 25: line 1, column 1: bad
+", summary(&reports));
+    }
+
+    #[test]
+    fn report_json() {
+        let report = Report {
+            day: 13,
+            answers: Ok(Answers { part1: Some(Ok("17".to_string())), part2: Some(Err("No solution".to_string())) }),
+            elapsed: Duration::from_micros(3),
+        };
+        assert_eq!(r#"{"day":13,"ok":false,"elapsed_ns":3000,"part1":{"answer":"17"},"part2":{"error":"No solution"},"error":null}"#,
+                   report.to_json().to_string());
+
+        let report = Report { day: 2, answers: Err("Day 2: bad \"x\"".to_string()), elapsed: Duration::from_nanos(5) };
+        assert_eq!(r#"{"day":2,"ok":false,"elapsed_ns":5,"part1":null,"part2":null,"error":"Day 2: bad \"x\""}"#,
+                   report.to_json().to_string());
+
+        let report = Report { day: 13, answers: Ok(Answers { part1: None, part2: Some(Ok(".#\n#.".to_string())) }), elapsed: Duration::ZERO };
+        assert_eq!(r#"{"day":13,"ok":true,"elapsed_ns":0,"part1":null,"part2":{"answer":".#\n#."},"error":null}"#,
+                   report.to_json().to_string());
+    }
+
+    #[test]
+    fn skip_missing_inputs() {
+        let input = |day: u32| if day == 1 { default_input(day) } else { format!("input/missing/day{}.txt", day) };
+        assert_eq!((vec![1], vec![19, 20]), split_missing(&[1, 19, 20], input));
+
+        assert_eq!("", skipped_note(&[]));
+        assert_eq!("\nSkipped days without an input file: 19, 20\n", skipped_note(&[19, 20]));
+        assert_eq!(r#"{"day":19,"ok":false,"elapsed_ns":0,"part1":null,"part2":null,"error":"no default input 'input/day19.txt'"}"#,
+                   skipped_json(19).to_string());
+    }
+}
@@ -0,0 +1,42 @@
+use std::fmt::{Display, Formatter};
+use std::io::BufRead;
+
+use crate::error::Result;
+
+/// `Solution` solves both parts of a day's puzzle.  Input is loaded into a typed puzzle once,
+/// and each part computes its answer from that puzzle.
+pub trait Solution {
+    /// Puzzle parsed from the input.
+    type Puzzle;
+
+    /// Answer to part 1.
+    type Part1: Display;
+
+    /// Answer to part 2.
+    type Part2: Display;
+
+    /// Loads the puzzle from the given reader, like a file, stdin, or a byte slice.  Returns an
+    /// error if the input can't be read or is malformed.
+    fn load<R: BufRead>(reader: R) -> Result<Self::Puzzle>;
+
+    /// Parses the puzzle out of the given input text.
+    fn parse(input: &str) -> Result<Self::Puzzle> {
+        Self::load(input.as_bytes())
+    }
+
+    /// Solves part 1 of the puzzle.
+    fn part1(puzzle: &Self::Puzzle) -> Result<Self::Part1>;
+
+    /// Solves part 2 of the puzzle.
+    fn part2(puzzle: &Self::Puzzle) -> Result<Self::Part2>;
+}
+
+/// `NoAnswer` is the answer to a part that doesn't have a puzzle of its own, like day 25's part 2.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub struct NoAnswer;
+
+impl Display for NoAnswer {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "-")
+    }
+}
@@ -0,0 +1,226 @@
+use std::collections::HashMap;
+use std::fmt::Write;
+
+use crate::error::{column_of, parse_num, ParseError};
+use crate::runner::{Answers, Part};
+
+/// Expected is a recorded answer to one part of a day's puzzle.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct Expected {
+    pub day: u32,
+    /// Part of the puzzle, either 1 or 2.
+    pub part: u8,
+    /// Input file that produces the answer.
+    pub input: String,
+    pub answer: String,
+}
+
+/// Manifest is a list of recorded answers.  Manifests are text files with one answer per line,
+/// and tab-separated columns for the day, part, input file, and answer.  Newlines, tabs, and
+/// backslashes in answers are escaped as `\n`, `\t`, and `\\`.  Blank lines and lines starting
+/// with `#` are ignored.
+#[derive(Debug, Clone, Default, Eq, PartialEq)]
+pub struct Manifest {
+    pub answers: Vec<Expected>,
+}
+
+impl Manifest {
+    /// Parses a manifest out of the given text.
+    pub fn parse(text: &str) -> Result<Self, ParseError> {
+        let mut answers = Vec::new();
+
+        for (i, line) in text.lines().enumerate() {
+            if line.trim().is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            let on_line = |e: ParseError| e.on_line(i + 1);
+            let fields = line.split('\t').collect::<Vec<&str>>();
+            if fields.len() != 4 {
+                return Err(on_line(ParseError::new(1, format!("expected 4 tab-separated fields, found {}", fields.len()))));
+            }
+
+            let day = parse_num(line, fields[0]).map_err(on_line)?;
+            let part = match fields[1] {
+                "1" => 1,
+                "2" => 2,
+                other => return Err(on_line(ParseError::at(line, other, format!("expected part 1 or 2, found '{}'", other)))),
+            };
+            let answer = unescape(fields[3])
+                .map_err(|i| on_line(ParseError::new(column_of(line, fields[3]) + i, "invalid escape")))?;
+
+            answers.push(Expected { day, part, input: fields[2].to_string(), answer });
+        }
+
+        Ok(Manifest { answers })
+    }
+}
+
+/// Outcome is the result of checking a recorded answer.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub enum Outcome {
+    /// The puzzle was solved, and the answer matched.
+    Pass,
+    /// The puzzle was solved, but the answer was different.
+    Mismatch { actual: String },
+    /// The puzzle couldn't be solved.
+    Fail { error: String },
+}
+
+/// Checks every recorded answer for the given days, or all days if none are given.  Answers are
+/// found by calling `solve` with a day, the parts to solve, and an input file, which happens once
+/// per day and input.
+pub fn check<F>(manifest: &Manifest, days: &[u32], mut solve: F) -> Vec<(Expected, Outcome)>
+    where F: FnMut(u32, Part, &str) -> Result<Answers, String> {
+    let expected = manifest.answers.iter()
+        .filter(|expected| days.is_empty() || days.contains(&expected.day))
+        .collect::<Vec<&Expected>>();
+
+    // Only solve the parts that have recorded answers.
+    let mut parts: HashMap<(u32, &str), Part> = HashMap::new();
+    for expected in &expected {
+        let part = if expected.part == 1 { Part::One } else { Part::Two };
+        parts.entry((expected.day, &expected.input))
+            .and_modify(|p| if *p != part { *p = Part::Both })
+            .or_insert(part);
+    }
+
+    let mut solved: HashMap<(u32, &str), Result<Answers, String>> = HashMap::new();
+    let mut checks = Vec::new();
+
+    for expected in expected {
+        let key = (expected.day, expected.input.as_str());
+        let answers = solved.entry(key)
+            .or_insert_with(|| solve(expected.day, parts[&key], &expected.input));
+
+        let actual = match answers {
+            Ok(answers) => {
+                let answer = if expected.part == 1 { &answers.part1 } else { &answers.part2 };
+                answer.as_ref().expect("Recorded parts are always solved").as_ref()
+            }
+            Err(error) => Err(&*error),
+        };
+
+        let outcome = match actual {
+            Ok(actual) if actual == &expected.answer => Outcome::Pass,
+            Ok(actual) => Outcome::Mismatch { actual: actual.clone() },
+            Err(error) => Outcome::Fail { error: error.clone() },
+        };
+
+        checks.push((expected.clone(), outcome));
+    }
+
+    checks
+}
+
+/// Returns a report of the checks for people to read, with a line per answer and a summary.
+pub fn report(checks: &[(Expected, Outcome)]) -> String {
+    let mut report = String::new();
+
+    for (expected, outcome) in checks {
+        write!(report, "Day {:>2} part {}: ", expected.day, expected.part).unwrap();
+
+        match outcome {
+            Outcome::Pass => writeln!(report, "pass"),
+            Outcome::Mismatch { actual } =>
+                writeln!(report, "mismatch - expected '{}', got '{}'", escape(&expected.answer), escape(actual)),
+            Outcome::Fail { error } => writeln!(report, "fail - {}", error),
+        }.unwrap();
+    }
+
+    let count = |f: fn(&Outcome) -> bool| checks.iter().filter(|(_, outcome)| f(outcome)).count();
+    writeln!(report, "\n{} passed, {} mismatched, {} failed.",
+             count(|o| matches!(o, Outcome::Pass)),
+             count(|o| matches!(o, Outcome::Mismatch { .. })),
+             count(|o| matches!(o, Outcome::Fail { .. }))).unwrap();
+
+    report
+}
+
+/// Escapes an answer so it fits on one line of a manifest.
+pub fn escape(answer: &str) -> String {
+    answer.replace('\\', "\\\\").replace('\n', "\\n").replace('\t', "\\t")
+}
+
+/// Reverses `escape`, or returns the index of an invalid escape.
+fn unescape(s: &str) -> Result<String, usize> {
+    let mut answer = String::new();
+    let mut chars = s.char_indices();
+
+    while let Some((i, c)) = chars.next() {
+        if c != '\\' {
+            answer.push(c);
+            continue;
+        }
+
+        match chars.next() {
+            Some((_, 'n')) => answer.push('\n'),
+            Some((_, 't')) => answer.push('\t'),
+            Some((_, '\\')) => answer.push('\\'),
+            _ => return Err(i),
+        }
+    }
+
+    Ok(answer)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn expected(day: u32, part: u8, answer: &str) -> Expected {
+        Expected { day, part, input: format!("input/day{}.txt", day), answer: answer.to_string() }
+    }
+
+    #[test]
+    fn parse_manifest() {
+        let manifest = Manifest::parse("# day\tpart\tinput\tanswer\n1\t1\tinput/day1.txt\t7\n\n13\t2\tinput/day13.txt\t# #\\n # \\\\\n").unwrap();
+
+        assert_eq!(vec![expected(1, 1, "7"), expected(13, 2, "# #\n # \\")], manifest.answers);
+    }
+
+    #[test]
+    fn parse_invalid_manifest() {
+        assert_eq!(Err(ParseError::new(3, "expected part 1 or 2, found '3'").on_line(2)),
+                   Manifest::parse("1\t1\tinput/day1.txt\t7\n1\t3\tinput/day1.txt\t7\n"));
+        assert_eq!(Err(ParseError::new(1, "expected 4 tab-separated fields, found 1")),
+                   Manifest::parse("1 1 input/day1.txt 7\n"));
+        assert_eq!(Err(ParseError::new(21, "invalid escape")),
+                   Manifest::parse("1\t1\tinput/day1.txt\ta\\b\n"));
+    }
+
+    #[test]
+    fn escape_round_trip() {
+        let answer = "# #\n\t\\";
+        assert_eq!("# #\\n\\t\\\\", escape(answer));
+        assert_eq!(Ok(answer.to_string()), unescape(&escape(answer)));
+    }
+
+    #[test]
+    fn check_answers() {
+        let manifest = Manifest { answers: vec![expected(1, 1, "7"), expected(1, 2, "5"), expected(2, 1, "150")] };
+        let mut solves = 0;
+
+        let checks = check(&manifest, &[], |day, part, _| {
+            solves += 1;
+            match day {
+                1 => {
+                    assert_eq!(Part::Both, part);
+                    Ok(Answers { part1: Some(Ok("7".to_string())), part2: Some(Ok("6".to_string())) })
+                }
+                _ => Err("Couldn't open input file".to_string()),
+            }
+        });
+
+        assert_eq!(2, solves);
+        assert_eq!(vec![
+            Outcome::Pass,
+            Outcome::Mismatch { actual: "6".to_string() },
+            Outcome::Fail { error: "Couldn't open input file".to_string() },
+        ], checks.into_iter().map(|(_, outcome)| outcome).collect::<Vec<Outcome>>());
+
+        let checks = check(&manifest, &[2], |_, _, _| Err("skipped".to_string()));
+        assert_eq!(1, checks.len());
+        assert_eq!("Day  2 part 1: fail - skipped\n\n0 passed, 0 mismatched, 1 failed.\n", report(&checks));
+    }
+}